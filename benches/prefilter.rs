@@ -0,0 +1,39 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use string_patterns::*;
+
+// Compares collection methods, which reject strings lacking required literals before running the regex,
+// with a plain loop over the compiled regex. Build with --no-default-features --features regex for a baseline without prefilters.
+
+fn sample_records() -> Vec<String> {
+  (0..20_000).map(|index| match index % 50 {
+    0 => format!("ERROR {} request to /api/items/{} failed after timeout", index, index * 7),
+    1 => format!("ERROR {} disk quota exceeded on volume {}", index, index % 9),
+    _ => format!("INFO {} request to /api/items/{} completed in {}ms with status 200", index, index * 7, index % 400),
+  }).collect()
+}
+
+fn bench_prefilter(c: &mut Criterion) {
+  let records = sample_records();
+  let patterns = [r#"ERROR.*timeout"#, r#"\w+ \d+ disk quota"#, r#"(?:quota|timeout) exceeded"#];
+  for pattern in patterns {
+    let mut group = c.benchmark_group(pattern);
+    group.bench_function("pattern_filter", |b| b.iter(|| black_box(records.pattern_filter(black_box(pattern), false))));
+    group.bench_function("pattern_matches", |b| b.iter(|| black_box(records.pattern_matches(black_box(pattern), false))));
+    group.bench_function("regex_loop", |b| b.iter(|| {
+      let re = build_regex(black_box(pattern), false).unwrap();
+      black_box(records.iter().filter(|record| re.is_match(record)).cloned().collect::<Vec<String>>())
+    }));
+    group.finish();
+  }
+  let ci_pattern = r#"error.*timeout"#;
+  let mut group = c.benchmark_group("case-insensitive");
+  group.bench_function("pattern_filter_ci", |b| b.iter(|| black_box(records.pattern_filter_ci(black_box(ci_pattern)))));
+  group.bench_function("regex_loop", |b| b.iter(|| {
+    let re = build_regex(black_box(ci_pattern), true).unwrap();
+    black_box(records.iter().filter(|record| re.is_match(record)).cloned().collect::<Vec<String>>())
+  }));
+  group.finish();
+}
+
+criterion_group!(benches, bench_prefilter);
+criterion_main!(benches);
@@ -0,0 +1,73 @@
+// Back-references always use fancy-regex, whichever backend is selected for other methods.
+// Captures, matches and errors are converted to the crate's own types, with runtime errors such as an exceeded
+// backtracking limit returned as Error::Runtime.
+use fancy_regex::Regex;
+use crate::engine::{Captures, Error, Match};
+
+/// Build a fancy-regex regular expression that may contain back-references such as \1 or \k<name>
+/// as well as look-around assertions and atomic groups.
+/// The case-insensitive flag is handled as in build_regex
+pub fn build_fancy_regex(pattern: &str, case_insensitive: bool) -> Result<Regex, Error> {
+  let mut parts: Vec<&str> = vec![];
+  if case_insensitive {
+    parts.push("(?i)");
+  }
+  parts.push(pattern);
+  Regex::new(&parts.concat()).map_err(Error::from)
+}
+
+/// Match, capture and replace methods for patterns with back-references, e.g. \b(\w+)\s+\1\b,
+/// which the Regex crate cannot express. Requires the backreferences feature.
+/// Result methods also return runtime errors, e.g. if the backtracking limit is exceeded.
+/// Only pattern_captures_backref_result and pattern_replace_backref_result need to be implemented
+pub trait PatternBackReference<'a> {
+
+  /// Yields a result with a vector of captures for all matches of a pattern with back-references
+  /// or an error if the regex does not compile or fails at runtime
+  fn pattern_captures_backref_result(&'a self, pattern: &str, case_insensitive: bool) -> Result<Vec<Captures<'a>>, Error>;
+
+  /// Yields a vector of captures for all matches, empty if the regex fails
+  fn pattern_captures_backref(&'a self, pattern: &str, case_insensitive: bool) -> Vec<Captures<'a>> {
+    self.pattern_captures_backref_result(pattern, case_insensitive).unwrap_or_default()
+  }
+
+  /// Yields a vector of outer matches for the whole pattern, empty if the regex fails
+  fn pattern_matches_backref(&'a self, pattern: &str, case_insensitive: bool) -> Vec<Match<'a>> {
+    self.pattern_captures_backref(pattern, case_insensitive).iter().filter_map(|captures| captures.get(0)).collect()
+  }
+
+  /// Apply a regular expression match with back-references or return an error
+  fn pattern_match_backref_result(&'a self, pattern: &str, case_insensitive: bool) -> Result<bool, Error> {
+    self.pattern_captures_backref_result(pattern, case_insensitive).map(|captures| !captures.is_empty())
+  }
+
+  /// Apply a regular expression match with back-references. NB: If the regex fails it will return false
+  fn pattern_match_backref(&'a self, pattern: &str, case_insensitive: bool) -> bool {
+    self.pattern_match_backref_result(pattern, case_insensitive).unwrap_or(false)
+  }
+
+  /// Replace all matches of a pattern with back-references, returning an error if the regex fails
+  fn pattern_replace_backref_result(&'a self, pattern: &str, replacement: &str, case_insensitive: bool) -> Result<String, Error>;
+
+  /// Replace all matches of a pattern with back-references. If the regex fails, nothing will be replaced
+  fn pattern_replace_backref(&'a self, pattern: &str, replacement: &str, case_insensitive: bool) -> String;
+}
+
+/// Implemented for &str and available to String too
+impl<'a> PatternBackReference<'a> for str {
+
+  fn pattern_captures_backref_result(&'a self, pattern: &str, case_insensitive: bool) -> Result<Vec<Captures<'a>>, Error> {
+    let re = build_fancy_regex(pattern, case_insensitive)?;
+    re.captures_iter(self).map(|captures| captures.map(Captures::from).map_err(Error::from)).collect()
+  }
+
+  fn pattern_replace_backref_result(&'a self, pattern: &str, replacement: &str, case_insensitive: bool) -> Result<String, Error> {
+    let re = build_fancy_regex(pattern, case_insensitive)?;
+    // a limit of 0 replaces all matches
+    re.try_replacen(self, 0, replacement).map(|replaced| replaced.to_string()).map_err(Error::from)
+  }
+
+  fn pattern_replace_backref(&'a self, pattern: &str, replacement: &str, case_insensitive: bool) -> String {
+    self.pattern_replace_backref_result(pattern, replacement, case_insensitive).unwrap_or(self.to_owned())
+  }
+}
@@ -0,0 +1,127 @@
+use crate::engine::{Error, Match, RegexExt};
+use crate::enums::WordBounds;
+use crate::utils::{build_regex, escape_class_char};
+
+// Configurable word boundaries for word methods that cannot rely on \b.
+// \b treats - and ' as boundaries and _ as a word character, so "e-mail" and "don't" cannot be matched as whole
+// words, and CJK text without spaces has no useful boundaries at all. A WordBoundary defines which characters
+// count as word characters. As the regex crate has no look-behind, the boundaries are emulated with a leading
+// group matching a non-word character or the start of the string, and a trailing non-capturing group matching
+// a non-word character or the end of the string.
+
+/// Defines which characters are part of words when matching whole words, word starts or word ends
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WordBoundary {
+  extra_word_chars: Vec<char>,
+  excluded_word_chars: Vec<char>,
+  whitespace_only: bool,
+}
+
+impl WordBoundary {
+  /// Word characters as with \b, i.e. letters, digits and underscores
+  pub fn new() -> WordBoundary {
+    WordBoundary::default()
+  }
+
+  /// Words are delimited by whitespace only, so punctuation is part of words.
+  /// Useful for CJK text or tokens such as e-mail addresses and version numbers
+  pub fn whitespace() -> WordBoundary {
+    WordBoundary { whitespace_only: true, ..WordBoundary::default() }
+  }
+
+  /// Adds characters that are part of words, e.g. "@." for e-mail addresses
+  pub fn with_word_chars(mut self, chars: &str) -> WordBoundary {
+    self.extra_word_chars.extend(chars.chars());
+    self
+  }
+
+  /// Removes characters from words, so they act as boundaries
+  pub fn without_word_chars(mut self, chars: &str) -> WordBoundary {
+    self.excluded_word_chars.extend(chars.chars());
+    self
+  }
+
+  /// Treats straight and curly apostrophes as word characters, so don't is a single word
+  pub fn with_apostrophes(self) -> WordBoundary {
+    self.with_word_chars("'’")
+  }
+
+  /// Treats hyphens as word characters, so e-mail is a single word
+  pub fn with_hyphens(self) -> WordBoundary {
+    self.with_word_chars("-‐")
+  }
+
+  /// Treats underscores as boundaries, so snake_case has two words
+  pub fn without_underscore(self) -> WordBoundary {
+    self.without_word_chars("_")
+  }
+
+  /// Regex subpattern matching one character that is not part of a word
+  pub fn non_word_pattern(&self) -> String {
+    if self.whitespace_only {
+      return r#"\s"#.to_owned();
+    }
+    let extra_chars: String = self.extra_word_chars.iter().filter(|ch| !self.excluded_word_chars.contains(ch)).map(|ch| escape_class_char(*ch)).collect();
+    let non_word_class = [r#"[^\w"#, &extra_chars, "]"].concat();
+    if self.excluded_word_chars.is_empty() {
+      return non_word_class;
+    }
+    let excluded_chars: String = self.excluded_word_chars.iter().map(|ch| escape_class_char(*ch)).collect();
+    ["(?:", &non_word_class, "|[", &excluded_chars, "])"].concat()
+  }
+
+  /// Converts a word pattern to a pattern with emulated word boundaries.
+  /// With start bounds the word is captured in group 2 after the leading context in group 1,
+  /// otherwise in group 1. The context characters are included in the whole match
+  pub fn to_pattern(&self, word: &str, bounds: WordBounds) -> String {
+    let non_word = self.non_word_pattern();
+    let mut parts: Vec<String> = vec![];
+    if matches!(bounds, WordBounds::Start | WordBounds::Both) {
+      parts.push(["(", &non_word, "|^)"].concat());
+    }
+    parts.push(["(", word, ")"].concat());
+    if matches!(bounds, WordBounds::End | WordBounds::Both) {
+      parts.push(["(?:", &non_word, "|$)"].concat());
+    }
+    parts.concat()
+  }
+}
+
+/// Finds the words matching the pattern within the boundaries, excluding context characters.
+/// After each match the search resumes at the end of the word, so a separator between two words
+/// serves as the trailing context of one and the leading context of the next
+pub(crate) fn find_bounded_words<'t>(text: &'t str, word: &str, bounds: WordBounds, boundary: &WordBoundary, case_insensitive: bool) -> Result<Vec<Match<'t>>, Error> {
+  let re = build_regex(&boundary.to_pattern(word, bounds), case_insensitive)?;
+  let word_group = if matches!(bounds, WordBounds::Start | WordBounds::Both) { 2 } else { 1 };
+  let mut matched_items: Vec<Match<'t>> = vec![];
+  let mut start = 0;
+  while start <= text.len() {
+    let Some(matched_item) = re.captures_from(text, start).and_then(|captures| captures.get(word_group)) else {
+      break;
+    };
+    matched_items.push(matched_item);
+    // empty matches resume one character later
+    start = if matched_item.end() > matched_item.start() {
+      matched_item.end()
+    } else {
+      matched_item.end() + text[matched_item.end()..].chars().next().map(|ch| ch.len_utf8()).unwrap_or(1)
+    };
+  }
+  Ok(matched_items)
+}
+
+/// Replaces the words matching the pattern within the boundaries, leaving context characters in place.
+/// Capture group references in the replacement refer to groups in the word pattern
+pub(crate) fn replace_bounded_words(text: &str, word: &str, replacement: &str, bounds: WordBounds, boundary: &WordBoundary, case_insensitive: bool) -> Result<String, Error> {
+  let matched_items = find_bounded_words(text, word, bounds, boundary, case_insensitive)?;
+  let word_re = build_regex(&["^(?:", word, ")$"].concat(), case_insensitive)?;
+  let mut output = String::with_capacity(text.len());
+  let mut position = 0;
+  for matched_item in matched_items {
+    output.push_str(&text[position..matched_item.start()]);
+    output.push_str(&word_re.replace_first_result(matched_item.as_str(), replacement)?);
+    position = matched_item.end();
+  }
+  output.push_str(&text[position..]);
+  Ok(output)
+}
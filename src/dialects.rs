@@ -0,0 +1,184 @@
+use crate::engine::escape_literal;
+use crate::enums::PatternDialect;
+use crate::utils::escape_class_char;
+
+// Translators from simpler pattern dialects to anchored regular expressions, so that glob, SQL LIKE and
+// wildcard patterns can be used wherever a regex is accepted. Translated patterns always match the whole string.
+// Unterminated [ and { in globs are matched literally, as in most shells, so translation never fails.
+
+/// Anchors a translated pattern to the whole string. Dot matches new lines, so % and * span multiple lines
+fn anchor(pattern: &str) -> String {
+  ["^(?s:", pattern, ")$"].concat()
+}
+
+fn escape_char(ch: char) -> String {
+  escape_literal(&ch.to_string())
+}
+
+/// Translates a glob to an anchored regex, where * matches any characters except /, ** also matches /,
+/// **/ matches zero or more directories, ? matches one character except /, [abc], [a-z] and [!abc] match
+/// character classes, {jpg,png} matches alternatives and \ escapes the next character
+pub fn glob_to_regex(glob: &str) -> String {
+  let chars: Vec<char> = glob.chars().collect();
+  anchor(&translate_glob(&chars))
+}
+
+/// Translates a SQL LIKE pattern to an anchored regex. % matches any characters, _ matches one character
+/// and the optional escape character matches the next character literally. Use a case-insensitive flag for ILIKE
+pub fn like_to_regex(pattern: &str, escape: Option<char>) -> String {
+  let mut output = String::new();
+  let mut chars = pattern.chars();
+  while let Some(ch) = chars.next() {
+    if Some(ch) == escape {
+      output.push_str(&escape_char(chars.next().unwrap_or(ch)));
+      continue;
+    }
+    match ch {
+      '%' => output.push_str(".*"),
+      '_' => output.push('.'),
+      _ => output.push_str(&escape_char(ch)),
+    }
+  }
+  anchor(&output)
+}
+
+/// Translates a simple wildcard pattern to an anchored regex. * matches any characters and ? matches one character
+pub fn wildcard_to_regex(pattern: &str) -> String {
+  let output: String = pattern.chars().map(|ch| match ch {
+    '*' => ".*".to_owned(),
+    '?' => ".".to_owned(),
+    _ => escape_char(ch),
+  }).collect();
+  anchor(&output)
+}
+
+/// Translates a pattern in any dialect to a regex. Regex patterns are returned as is
+pub fn dialect_to_regex(pattern: &str, dialect: PatternDialect) -> String {
+  match dialect {
+    PatternDialect::Regex => pattern.to_owned(),
+    PatternDialect::Glob => glob_to_regex(pattern),
+    PatternDialect::Like(escape) => like_to_regex(pattern, escape),
+    PatternDialect::Wildcard => wildcard_to_regex(pattern),
+  }
+}
+
+fn translate_glob(chars: &[char]) -> String {
+  let mut output = String::new();
+  let mut index = 0;
+  while index < chars.len() {
+    let ch = chars[index];
+    match ch {
+      '\\' => {
+        index += 1;
+        output.push_str(&escape_char(chars.get(index).copied().unwrap_or('\\')));
+      },
+      '*' => {
+        let start = index;
+        while chars.get(index + 1) == Some(&'*') {
+          index += 1;
+        }
+        if index == start {
+          output.push_str("[^/]*");
+        } else if (start == 0 || chars[start - 1] == '/') && chars.get(index + 1) == Some(&'/') {
+          index += 1;
+          output.push_str("(?:.*/)?");
+        } else {
+          output.push_str(".*");
+        }
+      },
+      '?' => output.push_str("[^/]"),
+      '[' => match find_class_end(chars, index) {
+        Some(end) => {
+          output.push_str(&translate_class(&chars[index + 1..end]));
+          index = end;
+        },
+        None => output.push_str(&escape_char(ch)),
+      },
+      '{' => match find_brace_end(chars, index) {
+        Some(end) => {
+          let alternatives: Vec<String> = split_alternatives(&chars[index + 1..end]).into_iter().map(translate_glob).collect();
+          output.push_str(&["(?:", &alternatives.join("|"), ")"].concat());
+          index = end;
+        },
+        None => output.push_str(&escape_char(ch)),
+      },
+      _ => output.push_str(&escape_char(ch)),
+    }
+    index += 1;
+  }
+  output
+}
+
+/// Index of the ] closing a class opened at start. A ] directly after [ or [! is part of the class
+fn find_class_end(chars: &[char], start: usize) -> Option<usize> {
+  let mut index = start + 1;
+  if matches!(chars.get(index), Some('!') | Some('^')) {
+    index += 1;
+  }
+  if chars.get(index) == Some(&']') {
+    index += 1;
+  }
+  chars[index.min(chars.len())..].iter().position(|ch| *ch == ']').map(|offset| index + offset)
+}
+
+/// Translates the contents of a glob class, with ! or ^ as negation and - between two characters as a range
+fn translate_class(content: &[char]) -> String {
+  let (negated, content) = match content.first() {
+    Some('!') | Some('^') => (true, &content[1..]),
+    _ => (false, content),
+  };
+  let mut output = String::from(if negated { "[^" } else { "[" });
+  for (index, ch) in content.iter().enumerate() {
+    if *ch == '-' && index > 0 && index + 1 < content.len() {
+      output.push('-');
+    } else {
+      output.push_str(&escape_class_char(*ch));
+    }
+  }
+  output.push(']');
+  output
+}
+
+/// Index of the } closing a brace group opened at start, skipping nested groups and escaped characters
+fn find_brace_end(chars: &[char], start: usize) -> Option<usize> {
+  let mut depth = 0;
+  let mut index = start;
+  while index < chars.len() {
+    match chars[index] {
+      '\\' => index += 1,
+      '{' => depth += 1,
+      '}' => {
+        depth -= 1;
+        if depth == 0 {
+          return Some(index);
+        }
+      },
+      _ => (),
+    }
+    index += 1;
+  }
+  None
+}
+
+/// Splits the contents of a brace group on top-level commas
+fn split_alternatives(content: &[char]) -> Vec<&[char]> {
+  let mut alternatives = vec![];
+  let mut depth = 0;
+  let mut start = 0;
+  let mut index = 0;
+  while index < content.len() {
+    match content[index] {
+      '\\' => index += 1,
+      '{' => depth += 1,
+      '}' => depth -= 1,
+      ',' if depth == 0 => {
+        alternatives.push(&content[start..index]);
+        start = index + 1;
+      },
+      _ => (),
+    }
+    index += 1;
+  }
+  alternatives.push(&content[start.min(content.len())..]);
+  alternatives
+}
@@ -0,0 +1,190 @@
+use std::fmt;
+use aho_corasick::{AhoCorasick, MatchKind};
+use crate::enums::WordBounds;
+use crate::utils::is_word_char;
+
+// Dictionary-based replacement for large literal word lists.
+// All words are compiled once into a single Aho-Corasick automaton, so a text is scanned in one pass however many
+// words the dictionary holds, instead of compiling and running one regex per word as replace_words does.
+// Words are matched literally. Word bounds apply only at word-character edges, so a dictionary entry like c++
+// still matches at the end of a word. Case-insensitive mode folds ASCII letters, while words with other letters
+// match in lower case, upper case or capitalised, e.g. émile, ÉMILE and Émile, but not if letters beyond ASCII
+// mix cases otherwise, e.g. smØrbrød for smørbrød.
+
+/// Error raised if a dictionary cannot be built because its automaton exceeds the size limits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DictionaryError(String);
+
+impl fmt::Display for DictionaryError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.0)
+  }
+}
+
+impl std::error::Error for DictionaryError {}
+
+/// Spellings of a word to search for. The automaton folds ASCII letters only, so in case-insensitive mode
+/// the lower-case, upper-case and capitalised forms of words with other letters are added
+fn case_variants(word: &str, case_insensitive: bool) -> Vec<String> {
+  let mut variants = vec![word.to_owned()];
+  if case_insensitive && !word.is_ascii() {
+    let lower = word.to_lowercase();
+    let mut chars = lower.chars();
+    let capitalized: String = chars.next().map(|ch| ch.to_uppercase().chain(chars).collect()).unwrap_or_default();
+    for variant in [word.to_uppercase(), capitalized, lower] {
+      if !variants.contains(&variant) {
+        variants.push(variant);
+      }
+    }
+  }
+  variants
+}
+
+/// Dictionary hit with byte offsets in the text and the index of its (word, replacement) pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DictionaryMatch {
+  pub start: usize,
+  pub end: usize,
+  pub index: usize,
+}
+
+/// Set of literal words with their replacements, compiled once and reused for many texts
+#[derive(Debug, Clone)]
+pub struct WordDictionary {
+  searcher: AhoCorasick,
+  pair_indices: Vec<usize>,
+  replacements: Vec<String>,
+  bounds: WordBounds,
+}
+
+impl WordDictionary {
+  /// Builds a dictionary from (word, replacement) pairs with boundary and case_insensitive options.
+  /// If a word occurs more than once, the first pair takes precedence.
+  /// Returns an error only if the automaton exceeds the size limits of the aho-corasick crate
+  pub fn new(pairs: &[(&str, &str)], bounds: WordBounds, case_insensitive: bool) -> Result<WordDictionary, DictionaryError> {
+    let mut words: Vec<String> = vec![];
+    let mut pair_indices: Vec<usize> = vec![];
+    for (index, (word, _)) in pairs.iter().enumerate() {
+      for variant in case_variants(word, case_insensitive) {
+        words.push(variant);
+        pair_indices.push(index);
+      }
+    }
+    let searcher = AhoCorasick::builder()
+      .match_kind(MatchKind::Standard)
+      .ascii_case_insensitive(case_insensitive)
+      .build(&words)
+      .map_err(|error| DictionaryError(error.to_string()))?;
+    let replacements = pairs.iter().map(|(_, replacement)| replacement.to_string()).collect();
+    Ok(WordDictionary { searcher, pair_indices, replacements, bounds })
+  }
+
+  /// Builds a dictionary matching whole words only
+  pub fn whole_words(pairs: &[(&str, &str)], case_insensitive: bool) -> Result<WordDictionary, DictionaryError> {
+    WordDictionary::new(pairs, WordBounds::Both, case_insensitive)
+  }
+
+  /// Number of (word, replacement) pairs
+  pub fn len(&self) -> usize {
+    self.replacements.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.replacements.is_empty()
+  }
+
+  /// Returns the replacement of a pair by its index
+  pub fn replacement(&self, index: usize) -> Option<&str> {
+    self.replacements.get(index).map(|replacement| replacement.as_str())
+  }
+
+  /// True if the match satisfies the word bounds at whichever edges start or end with a word character
+  fn is_within_bounds(&self, text: &str, start: usize, end: usize) -> bool {
+    let check_start = matches!(self.bounds, WordBounds::Start | WordBounds::Both);
+    let check_end = matches!(self.bounds, WordBounds::End | WordBounds::Both);
+    let matched = &text[start..end];
+    let start_ok = !check_start
+      || !matched.chars().next().is_some_and(is_word_char)
+      || !text[..start].chars().next_back().is_some_and(is_word_char);
+    let end_ok = !check_end
+      || !matched.chars().next_back().is_some_and(is_word_char)
+      || !text[end..].chars().next().is_some_and(is_word_char);
+    start_ok && end_ok
+  }
+
+  /// Returns the non-overlapping leftmost-longest dictionary words within the word bounds.
+  /// Longer words that fail the bounds give way to shorter words at the same position, e.g. new in new yorker
+  pub fn find_matches(&self, text: &str) -> Vec<DictionaryMatch> {
+    let mut candidates: Vec<DictionaryMatch> = self.searcher.find_overlapping_iter(text)
+      .filter(|hit| hit.start() < hit.end() && self.is_within_bounds(text, hit.start(), hit.end()))
+      .map(|hit| DictionaryMatch { start: hit.start(), end: hit.end(), index: self.pair_indices[hit.pattern().as_usize()] })
+      .collect();
+    candidates.sort_by_key(|hit| (hit.start, std::cmp::Reverse(hit.end), hit.index));
+    let mut matches: Vec<DictionaryMatch> = vec![];
+    let mut position = 0;
+    for hit in candidates {
+      if hit.start >= position {
+        position = hit.end;
+        matches.push(hit);
+      }
+    }
+    matches
+  }
+
+  /// True if the text contains any dictionary word within the word bounds
+  pub fn is_match(&self, text: &str) -> bool {
+    self.searcher.find_overlapping_iter(text).any(|hit| hit.start() < hit.end() && self.is_within_bounds(text, hit.start(), hit.end()))
+  }
+
+  /// Replaces all dictionary words in one pass. Replacements are inserted literally
+  pub fn replace(&self, text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut position = 0;
+    for hit in self.find_matches(text) {
+      output.push_str(&text[position..hit.start]);
+      output.push_str(&self.replacements[hit.index]);
+      position = hit.end;
+    }
+    output.push_str(&text[position..]);
+    output
+  }
+}
+
+/// Methods to replace words from a prebuilt WordDictionary in strings or vectors of strings.
+/// Only replace_with_dictionary needs to be implemented
+pub trait DictionaryReplace {
+  /// Replaces all dictionary words in one pass with their replacements
+  fn replace_with_dictionary(&self, dictionary: &WordDictionary) -> Self where Self:Sized;
+
+  /// Replaces whole words from (word, replacement) pairs in one pass with a case_insensitive flag.
+  /// Builds a dictionary for a single call, so reuse a WordDictionary for repeated replacements.
+  /// Returns the input unchanged if the dictionary cannot be built
+  fn replace_dictionary_words(&self, pairs: &[(&str, &str)], case_insensitive: bool) -> Self where Self:Sized + Clone {
+    match WordDictionary::whole_words(pairs, case_insensitive) {
+      Ok(dictionary) => self.replace_with_dictionary(&dictionary),
+      Err(_) => self.clone(),
+    }
+  }
+
+  /// Replaces whole words from (word, replacement) pairs in one pass in case-insensitive mode
+  fn replace_dictionary_words_ci(&self, pairs: &[(&str, &str)]) -> Self where Self:Sized + Clone {
+    self.replace_dictionary_words(pairs, true)
+  }
+
+  /// Replaces whole words from (word, replacement) pairs in one pass in case-sensitive mode
+  fn replace_dictionary_words_cs(&self, pairs: &[(&str, &str)]) -> Self where Self:Sized + Clone {
+    self.replace_dictionary_words(pairs, false)
+  }
+}
+
+impl DictionaryReplace for String {
+  fn replace_with_dictionary(&self, dictionary: &WordDictionary) -> String {
+    dictionary.replace(self)
+  }
+}
+
+impl DictionaryReplace for Vec<String> {
+  fn replace_with_dictionary(&self, dictionary: &WordDictionary) -> Vec<String> {
+    self.iter().map(|text| dictionary.replace(text)).collect()
+  }
+}
@@ -0,0 +1,571 @@
+// Selects the regular expression backend and provides a common set of methods used by all trait implementations.
+// The default backend is the regex crate. The fancy feature swaps it for fancy-regex, which adds back-references,
+// look-around assertions and atomic groups at the cost of backtracking.
+// The lite feature builds against regex-lite for smaller binaries and faster compilation, with ASCII-only
+// \w, \d, \s and \b classes, ASCII-only case-insensitive matching and no \p{..} Unicode classes.
+// If more than one backend is enabled, fancy takes precedence over lite and lite over regex.
+// Regex, Error, Captures and Match are crate types wrapping those of the selected backend, so enabling another
+// backend, e.g. through a dependency, changes how patterns are matched but never the public API.
+// With fancy-regex, runtime errors such as an exceeded backtracking limit are returned by _result methods
+// as Error::Runtime and treated as non-matches by all other methods.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::ops::{Index, Range};
+
+#[cfg(feature = "fancy")]
+use fancy_regex as backend;
+
+#[cfg(all(feature = "lite", not(feature = "fancy")))]
+use regex_lite as backend;
+
+#[cfg(not(any(feature = "lite", feature = "fancy")))]
+use regex as backend;
+
+#[cfg(not(any(feature = "regex", feature = "lite", feature = "fancy")))]
+compile_error!("string-patterns requires the regex (default), lite or fancy feature");
+
+/// Errors raised by the selected backend when compiling or running a regular expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+  /// The pattern is not a valid regular expression, with the backend's description of the problem
+  Syntax(String),
+  /// The compiled regular expression exceeds the backend's size limit in bytes
+  CompiledTooBig(usize),
+  /// The regular expression failed while matching, e.g. when fancy-regex exceeds its backtracking limit
+  Runtime(String),
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Error::Syntax(message) | Error::Runtime(message) => write!(f, "{}", message),
+      Error::CompiledTooBig(limit) => write!(f, "Compiled regex exceeds size limit of {} bytes.", limit),
+    }
+  }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(feature = "regex")]
+impl From<regex::Error> for Error {
+  fn from(error: regex::Error) -> Self {
+    match error {
+      regex::Error::CompiledTooBig(limit) => Error::CompiledTooBig(limit),
+      regex::Error::Syntax(message) => Error::Syntax(message),
+      _ => Error::Syntax(error.to_string()),
+    }
+  }
+}
+
+#[cfg(feature = "lite")]
+impl From<regex_lite::Error> for Error {
+  fn from(error: regex_lite::Error) -> Self {
+    Error::Syntax(error.to_string())
+  }
+}
+
+#[cfg(any(feature = "fancy", feature = "backreferences"))]
+impl From<fancy_regex::Error> for Error {
+  fn from(error: fancy_regex::Error) -> Self {
+    match error {
+      fancy_regex::Error::RuntimeError(_) => Error::Runtime(error.to_string()),
+      _ => Error::Syntax(error.to_string()),
+    }
+  }
+}
+
+/// A single match with its byte offsets in the searched text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match<'t> {
+  text: &'t str,
+  start: usize,
+}
+
+impl<'t> Match<'t> {
+  /// Creates a match from the matched text and its byte offset in the searched text
+  #[cfg_attr(not(any(feature = "segmentation", feature = "backreferences")), allow(dead_code))]
+  pub(crate) fn new(text: &'t str, start: usize) -> Match<'t> {
+    Match { text, start }
+  }
+
+  /// Byte offset of the start of the match
+  pub fn start(&self) -> usize {
+    self.start
+  }
+
+  /// Byte offset immediately after the end of the match
+  pub fn end(&self) -> usize {
+    self.start + self.text.len()
+  }
+
+  /// Byte range of the match in the searched text
+  pub fn range(&self) -> Range<usize> {
+    self.start()..self.end()
+  }
+
+  /// The matched text
+  pub fn as_str(&self) -> &'t str {
+    self.text
+  }
+
+  /// Length of the match in bytes
+  pub fn len(&self) -> usize {
+    self.text.len()
+  }
+
+  /// Whether the match is empty, e.g. for a zero-width assertion
+  pub fn is_empty(&self) -> bool {
+    self.text.is_empty()
+  }
+}
+
+impl<'t> From<backend::Match<'t>> for Match<'t> {
+  fn from(matched_item: backend::Match<'t>) -> Self {
+    Match { text: matched_item.as_str(), start: matched_item.start() }
+  }
+}
+
+// back-references use fancy-regex even if another backend is selected
+#[cfg(all(feature = "backreferences", not(feature = "fancy")))]
+impl<'t> From<fancy_regex::Match<'t>> for Match<'t> {
+  fn from(matched_item: fancy_regex::Match<'t>) -> Self {
+    Match::new(matched_item.as_str(), matched_item.start())
+  }
+}
+
+impl<'t> From<Match<'t>> for &'t str {
+  fn from(matched_item: Match<'t>) -> Self {
+    matched_item.as_str()
+  }
+}
+
+/// Capture groups of a single match. Group 0 is the whole match
+#[derive(Debug)]
+pub struct Captures<'t>(CaptureGroups<'t>);
+
+/// Captures of the selected backend, or of fancy-regex for back-references with another backend
+#[derive(Debug)]
+enum CaptureGroups<'t> {
+  Backend(backend::Captures<'t>),
+  #[cfg(all(feature = "backreferences", not(feature = "fancy")))]
+  Fancy(fancy_regex::Captures<'t>),
+}
+
+impl<'t> From<backend::Captures<'t>> for Captures<'t> {
+  fn from(captures: backend::Captures<'t>) -> Self {
+    Captures(CaptureGroups::Backend(captures))
+  }
+}
+
+#[cfg(all(feature = "backreferences", not(feature = "fancy")))]
+impl<'t> From<fancy_regex::Captures<'t>> for Captures<'t> {
+  fn from(captures: fancy_regex::Captures<'t>) -> Self {
+    Captures(CaptureGroups::Fancy(captures))
+  }
+}
+
+impl<'t> Captures<'t> {
+  /// Returns the capture group with the given index if it participated in the match
+  pub fn get(&self, index: usize) -> Option<Match<'t>> {
+    match &self.0 {
+      CaptureGroups::Backend(captures) => captures.get(index).map(Match::from),
+      #[cfg(all(feature = "backreferences", not(feature = "fancy")))]
+      CaptureGroups::Fancy(captures) => captures.get(index).map(Match::from),
+    }
+  }
+
+  /// Returns the named capture group if it participated in the match
+  pub fn name(&self, name: &str) -> Option<Match<'t>> {
+    match &self.0 {
+      CaptureGroups::Backend(captures) => captures.name(name).map(Match::from),
+      #[cfg(all(feature = "backreferences", not(feature = "fancy")))]
+      CaptureGroups::Fancy(captures) => captures.name(name).map(Match::from),
+    }
+  }
+
+  /// Number of capture groups including group 0, whether they participated in the match or not
+  pub fn len(&self) -> usize {
+    match &self.0 {
+      CaptureGroups::Backend(captures) => captures.len(),
+      #[cfg(all(feature = "backreferences", not(feature = "fancy")))]
+      CaptureGroups::Fancy(captures) => captures.len(),
+    }
+  }
+
+  /// Always false, as group 0 is always present
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// Iterates over all capture groups in order, with None for groups that did not participate
+  pub fn iter(&self) -> impl Iterator<Item = Option<Match<'t>>> + '_ {
+    (0..self.len()).map(|index| self.get(index))
+  }
+
+  /// Appends the replacement to dst, expanding $1, ${1}, $name and ${name} references to capture groups
+  pub fn expand(&self, replacement: &str, dst: &mut String) {
+    match &self.0 {
+      CaptureGroups::Backend(captures) => captures.expand(replacement, dst),
+      #[cfg(all(feature = "backreferences", not(feature = "fancy")))]
+      CaptureGroups::Fancy(captures) => captures.expand(replacement, dst),
+    }
+  }
+}
+
+impl Index<usize> for Captures<'_> {
+  type Output = str;
+
+  /// Returns the text of a capture group, panicking if the group did not participate
+  fn index(&self, index: usize) -> &str {
+    match &self.0 {
+      CaptureGroups::Backend(captures) => &captures[index],
+      #[cfg(all(feature = "backreferences", not(feature = "fancy")))]
+      CaptureGroups::Fancy(captures) => &captures[index],
+    }
+  }
+}
+
+impl Index<&str> for Captures<'_> {
+  type Output = str;
+
+  /// Returns the text of a named capture group, panicking if the group did not participate
+  fn index(&self, name: &str) -> &str {
+    match &self.0 {
+      CaptureGroups::Backend(captures) => &captures[name],
+      #[cfg(all(feature = "backreferences", not(feature = "fancy")))]
+      CaptureGroups::Fancy(captures) => &captures[name],
+    }
+  }
+}
+
+/// A compiled regular expression for the selected backend
+#[derive(Debug, Clone)]
+pub struct Regex(backend::Regex);
+
+impl Regex {
+  /// Compiles a regular expression, returning an error if the pattern is invalid or too large
+  pub fn new(pattern: &str) -> Result<Regex, Error> {
+    backend::Regex::new(pattern).map(Regex).map_err(Error::from)
+  }
+
+  /// The source pattern
+  pub fn as_str(&self) -> &str {
+    self.0.as_str()
+  }
+
+  /// Returns true if the regex matches the text, false if not or if the regex fails at runtime
+  pub fn is_match(&self, text: &str) -> bool {
+    self.matches_text(text)
+  }
+
+  /// Returns the first match if any
+  pub fn find<'t>(&self, text: &'t str) -> Option<Match<'t>> {
+    self.find_first(text)
+  }
+
+  /// Returns an iterator over all non-overlapping matches
+  pub fn find_iter<'r, 't>(&'r self, text: &'t str) -> impl Iterator<Item = Match<'t>> + 'r where 't: 'r {
+    self.find_all(text)
+  }
+
+  /// Returns the captures of the first match if any
+  pub fn captures<'t>(&self, text: &'t str) -> Option<Captures<'t>> {
+    self.captures_first(text)
+  }
+
+  /// Returns an iterator over the captures of all non-overlapping matches
+  pub fn captures_iter<'r, 't>(&'r self, text: &'t str) -> impl Iterator<Item = Captures<'t>> + 'r where 't: 'r {
+    self.captures_all(text)
+  }
+
+  /// Number of capture groups including group 0
+  pub fn captures_len(&self) -> usize {
+    self.0.captures_len()
+  }
+}
+
+// Methods of the backend's Regex, delegated so that the wrapper can be used in its place.
+// Replacements accept any Replacer of the backend crate, e.g. a &str with $1 references or a closure
+#[cfg(not(feature = "fancy"))]
+impl Regex {
+  /// Replaces the first match, expanding capture group references in the replacement
+  pub fn replace<'t, R: backend::Replacer>(&self, text: &'t str, rep: R) -> Cow<'t, str> {
+    self.0.replace(text, rep)
+  }
+
+  /// Replaces all non-overlapping matches, expanding capture group references in the replacement
+  pub fn replace_all<'t, R: backend::Replacer>(&self, text: &'t str, rep: R) -> Cow<'t, str> {
+    self.0.replace_all(text, rep)
+  }
+
+  /// Replaces up to limit matches, or all matches if limit is 0
+  pub fn replacen<'t, R: backend::Replacer>(&self, text: &'t str, limit: usize, rep: R) -> Cow<'t, str> {
+    self.0.replacen(text, limit, rep)
+  }
+
+  /// Returns an iterator over the substrings between matches
+  pub fn split<'r, 't>(&'r self, text: &'t str) -> impl Iterator<Item = &'t str> + 'r where 't: 'r {
+    self.0.split(text)
+  }
+
+  /// Returns an iterator over no more than limit substrings between matches, the last holding the rest of the text
+  pub fn splitn<'r, 't>(&'r self, text: &'t str, limit: usize) -> impl Iterator<Item = &'t str> + 'r where 't: 'r {
+    self.0.splitn(text, limit)
+  }
+
+  /// Returns the end offset of the earliest point where a match is found, which may be shorter than the leftmost-first match
+  pub fn shortest_match(&self, text: &str) -> Option<usize> {
+    self.0.shortest_match(text)
+  }
+
+  /// Returns the first match starting at or after the byte offset, with anchors and word boundaries evaluated against the whole text
+  pub fn find_at<'t>(&self, text: &'t str, start: usize) -> Option<Match<'t>> {
+    self.find_from(text, start)
+  }
+
+  /// Returns the captures of the first match starting at or after the byte offset
+  pub fn captures_at<'t>(&self, text: &'t str, start: usize) -> Option<Captures<'t>> {
+    self.captures_from(text, start)
+  }
+
+  /// Iterates over the names of all capture groups, with None for unnamed groups including group 0
+  pub fn capture_names(&self) -> impl Iterator<Item = Option<&str>> + '_ {
+    self.0.capture_names()
+  }
+}
+
+// fancy-regex panics on runtime errors in replace and returns results from split, so runtime errors end
+// replacements with the text unchanged and iteration at the error, as with the other non-result methods
+#[cfg(feature = "fancy")]
+impl Regex {
+  /// Replaces the first match, expanding capture group references in the replacement
+  pub fn replace<'t, R: backend::Replacer>(&self, text: &'t str, rep: R) -> Cow<'t, str> {
+    self.replacen(text, 1, rep)
+  }
+
+  /// Replaces all non-overlapping matches, expanding capture group references in the replacement
+  pub fn replace_all<'t, R: backend::Replacer>(&self, text: &'t str, rep: R) -> Cow<'t, str> {
+    self.replacen(text, 0, rep)
+  }
+
+  /// Replaces up to limit matches, or all matches if limit is 0
+  pub fn replacen<'t, R: backend::Replacer>(&self, text: &'t str, limit: usize, rep: R) -> Cow<'t, str> {
+    self.0.try_replacen(text, limit, rep).unwrap_or(Cow::Borrowed(text))
+  }
+
+  /// Returns an iterator over the substrings between matches
+  pub fn split<'r, 't>(&'r self, text: &'t str) -> impl Iterator<Item = &'t str> + 'r where 't: 'r {
+    self.0.split(text).map_while(Result::ok)
+  }
+
+  /// Returns an iterator over no more than limit substrings between matches, the last holding the rest of the text
+  pub fn splitn<'r, 't>(&'r self, text: &'t str, limit: usize) -> impl Iterator<Item = &'t str> + 'r where 't: 'r {
+    self.0.splitn(text, limit).map_while(Result::ok)
+  }
+
+  /// Returns the end offset of the first match, as fancy-regex has no shortest match search
+  pub fn shortest_match(&self, text: &str) -> Option<usize> {
+    self.find_first(text).map(|matched_item| matched_item.end())
+  }
+
+  /// Returns the first match starting at or after the byte offset, with anchors and word boundaries evaluated against the whole text
+  pub fn find_at<'t>(&self, text: &'t str, start: usize) -> Option<Match<'t>> {
+    self.find_from(text, start)
+  }
+
+  /// Returns the captures of the first match starting at or after the byte offset
+  pub fn captures_at<'t>(&self, text: &'t str, start: usize) -> Option<Captures<'t>> {
+    self.captures_from(text, start)
+  }
+
+  /// Iterates over the names of all capture groups, with None for unnamed groups including group 0
+  pub fn capture_names(&self) -> impl Iterator<Item = Option<&str>> + '_ {
+    self.0.capture_names()
+  }
+}
+
+impl fmt::Display for Regex {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.as_str())
+  }
+}
+
+impl std::str::FromStr for Regex {
+  type Err = Error;
+
+  fn from_str(pattern: &str) -> Result<Regex, Error> {
+    Regex::new(pattern)
+  }
+}
+
+/// Name of the backend selected by the enabled features: fancy-regex, regex-lite or regex.
+/// As features are unified across a dependency graph, this shows which one applies when several are enabled
+pub fn backend_name() -> &'static str {
+  if cfg!(feature = "fancy") {
+    "fancy-regex"
+  } else if cfg!(feature = "lite") {
+    "regex-lite"
+  } else {
+    "regex"
+  }
+}
+
+/// Escapes all regular expression meta characters, so that the text is matched literally
+#[cfg(feature = "fancy")]
+pub(crate) fn escape_literal(text: &str) -> String {
+  backend::escape(text).into_owned()
+}
+
+/// Escapes all regular expression meta characters, so that the text is matched literally
+#[cfg(all(feature = "lite", not(feature = "fancy")))]
+pub(crate) fn escape_literal(text: &str) -> String {
+  backend::escape(text)
+}
+
+/// Escapes all regular expression meta characters, so that the text is matched literally
+#[cfg(not(any(feature = "lite", feature = "fancy")))]
+pub(crate) fn escape_literal(text: &str) -> String {
+  backend::escape(text)
+}
+
+/// Common regex methods for all backends.
+/// Methods returning results propagate runtime errors, while other methods stop at the first runtime error
+pub(crate) trait RegexExt {
+  /// Returns true if the regex matches the text or an error if the regex fails at runtime
+  fn is_match_result(&self, text: &str) -> Result<bool, Error>;
+
+  /// Returns true if the regex matches the text, false if not or if the regex fails at runtime
+  fn matches_text(&self, text: &str) -> bool {
+    self.is_match_result(text).unwrap_or(false)
+  }
+
+  /// Returns the first match if any
+  fn find_first<'t>(&self, text: &'t str) -> Option<Match<'t>>;
+
+  /// Returns an iterator over all non-overlapping matches
+  fn find_all<'t>(&self, text: &'t str) -> impl Iterator<Item = Match<'t>>;
+
+  /// Returns the first match starting at or after the given byte offset.
+  /// Unlike slicing the text, anchors and word boundaries are evaluated against the whole text
+  fn find_from<'t>(&self, text: &'t str, start: usize) -> Option<Match<'t>>;
+
+  /// Returns the captures of the first match if any
+  fn captures_first<'t>(&self, text: &'t str) -> Option<Captures<'t>>;
+
+  /// Returns the captures of the first match starting at or after the given byte offset.
+  /// Unlike slicing the text, anchors and word boundaries are evaluated against the whole text
+  fn captures_from<'t>(&self, text: &'t str, start: usize) -> Option<Captures<'t>>;
+
+  /// Returns an iterator over the captures of all non-overlapping matches
+  fn captures_all<'t>(&self, text: &'t str) -> impl Iterator<Item = Captures<'t>>;
+
+  /// Replaces all matches, expanding capture group references in the replacement
+  fn replace_all_result(&self, text: &str, replacement: &str) -> Result<String, Error>;
+
+  /// Replaces the first match, expanding capture group references in the replacement
+  fn replace_first_result(&self, text: &str, replacement: &str) -> Result<String, Error>;
+
+  /// Splits the text on all matches
+  fn split_result<'t>(&self, text: &'t str) -> Result<Vec<&'t str>, Error>;
+
+  /// Splits the text on matches into no more than limit parts
+  fn splitn_result<'t>(&self, text: &'t str, limit: usize) -> Result<Vec<&'t str>, Error>;
+}
+
+// regex-lite mirrors the regex API, so both share this implementation
+#[cfg(not(feature = "fancy"))]
+impl RegexExt for Regex {
+  fn is_match_result(&self, text: &str) -> Result<bool, Error> {
+    Ok(self.0.is_match(text))
+  }
+
+  fn find_first<'t>(&self, text: &'t str) -> Option<Match<'t>> {
+    self.0.find(text).map(Match::from)
+  }
+
+  fn find_all<'t>(&self, text: &'t str) -> impl Iterator<Item = Match<'t>> {
+    self.0.find_iter(text).map(Match::from)
+  }
+
+  fn find_from<'t>(&self, text: &'t str, start: usize) -> Option<Match<'t>> {
+    self.0.find_at(text, start).map(Match::from)
+  }
+
+  fn captures_first<'t>(&self, text: &'t str) -> Option<Captures<'t>> {
+    self.0.captures(text).map(Captures::from)
+  }
+
+  fn captures_from<'t>(&self, text: &'t str, start: usize) -> Option<Captures<'t>> {
+    self.0.captures_at(text, start).map(Captures::from)
+  }
+
+  fn captures_all<'t>(&self, text: &'t str) -> impl Iterator<Item = Captures<'t>> {
+    self.0.captures_iter(text).map(Captures::from)
+  }
+
+  fn replace_all_result(&self, text: &str, replacement: &str) -> Result<String, Error> {
+    Ok(self.0.replace_all(text, replacement).to_string())
+  }
+
+  fn replace_first_result(&self, text: &str, replacement: &str) -> Result<String, Error> {
+    Ok(self.0.replace(text, replacement).to_string())
+  }
+
+  fn split_result<'t>(&self, text: &'t str) -> Result<Vec<&'t str>, Error> {
+    Ok(self.0.split(text).collect())
+  }
+
+  fn splitn_result<'t>(&self, text: &'t str, limit: usize) -> Result<Vec<&'t str>, Error> {
+    Ok(self.0.splitn(text, limit).collect())
+  }
+}
+
+#[cfg(feature = "fancy")]
+impl RegexExt for Regex {
+  fn is_match_result(&self, text: &str) -> Result<bool, Error> {
+    self.0.is_match(text).map_err(Error::from)
+  }
+
+  fn find_first<'t>(&self, text: &'t str) -> Option<Match<'t>> {
+    self.0.find(text).ok().flatten().map(Match::from)
+  }
+
+  fn find_all<'t>(&self, text: &'t str) -> impl Iterator<Item = Match<'t>> {
+    self.0.find_iter(text).map_while(Result::ok).map(Match::from)
+  }
+
+  fn find_from<'t>(&self, text: &'t str, start: usize) -> Option<Match<'t>> {
+    self.0.find_from_pos(text, start).ok().flatten().map(Match::from)
+  }
+
+  fn captures_first<'t>(&self, text: &'t str) -> Option<Captures<'t>> {
+    self.0.captures(text).ok().flatten().map(Captures::from)
+  }
+
+  fn captures_from<'t>(&self, text: &'t str, start: usize) -> Option<Captures<'t>> {
+    self.0.captures_from_pos(text, start).ok().flatten().map(Captures::from)
+  }
+
+  fn captures_all<'t>(&self, text: &'t str) -> impl Iterator<Item = Captures<'t>> {
+    self.0.captures_iter(text).map_while(Result::ok).map(Captures::from)
+  }
+
+  // fancy-regex's replace_all panics on runtime errors, so try_replacen is used instead. A limit of 0 replaces all matches
+  fn replace_all_result(&self, text: &str, replacement: &str) -> Result<String, Error> {
+    self.0.try_replacen(text, 0, replacement).map(|replaced| replaced.to_string()).map_err(Error::from)
+  }
+
+  fn replace_first_result(&self, text: &str, replacement: &str) -> Result<String, Error> {
+    self.0.try_replacen(text, 1, replacement).map(|replaced| replaced.to_string()).map_err(Error::from)
+  }
+
+  fn split_result<'t>(&self, text: &'t str) -> Result<Vec<&'t str>, Error> {
+    self.0.split(text).collect::<Result<Vec<&'t str>, _>>().map_err(Error::from)
+  }
+
+  fn splitn_result<'t>(&self, text: &'t str, limit: usize) -> Result<Vec<&'t str>, Error> {
+    self.0.splitn(text, limit).collect::<Result<Vec<&'t str>, _>>().map_err(Error::from)
+  }
+}
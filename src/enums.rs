@@ -1,5 +1,8 @@
+use crate::boundary::WordBoundary;
+
 
 /// Defines the start, end and both bounds of a word
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WordBounds {
   None,
   Start,
@@ -17,4 +20,69 @@ impl WordBounds {
       _ => word.to_owned(),
     }
   }
+
+  /// Convert word bounds with a custom word boundary definition instead of \b
+  pub fn to_pattern_with(&self, word: &str, boundary: &WordBoundary) -> String {
+    boundary.to_pattern(word, *self)
+  }
+}
+
+/// Pattern syntax accepted by dialect-aware match and filter methods.
+/// All dialects other than Regex are translated to regular expressions matching the whole string
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatternDialect {
+  /// Regular expression, used as is
+  Regex,
+  /// Shell glob with *, **, ?, [abc], [!abc] and {a,b}
+  Glob,
+  /// SQL LIKE with % and _ and an optional escape character. Use a case-insensitive flag for ILIKE
+  Like(Option<char>),
+  /// Simple * and ? wildcards
+  Wildcard,
 }
+
+/// English inflections generated for inflection-aware word methods, in order of precedence
+/// where two inflections share a spelling, e.g. the past tense and past participle of walk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Inflection {
+  /// The word as given, e.g. mouse or run
+  Base,
+  /// Plural nouns and the third person singular of verbs, e.g. mice or runs
+  Plural,
+  /// The -ing form, e.g. running
+  PresentParticiple,
+  /// The simple past, e.g. ran or walked
+  Past,
+  /// The past participle, e.g. run or walked
+  PastParticiple,
+}
+
+/// Look-ahead and look-behind conditions that emulate (?=...), (?!...), (?<=...) and (?<!...),
+/// which are not supported by the Regex crate. Each variant holds a regular expression
+/// that must (or must not) match immediately after or before the main match.
+pub enum LookAround<'a> {
+  FollowedBy(&'a str),
+  NotFollowedBy(&'a str),
+  PrecededBy(&'a str),
+  NotPrecededBy(&'a str),
+}
+
+impl<'a> LookAround<'a> {
+  /// Returns the pattern of the condition
+  pub fn pattern(&self) -> &'a str {
+    match self {
+      LookAround::FollowedBy(pattern) | LookAround::NotFollowedBy(pattern) | LookAround::PrecededBy(pattern) | LookAround::NotPrecededBy(pattern) => pattern,
+    }
+  }
+
+  /// True for look-ahead conditions
+  pub fn is_ahead(&self) -> bool {
+    matches!(self, LookAround::FollowedBy(_) | LookAround::NotFollowedBy(_))
+  }
+
+  /// True for positive conditions
+  pub fn is_positive(&self) -> bool {
+    matches!(self, LookAround::FollowedBy(_) | LookAround::PrecededBy(_))
+  }
+}
+
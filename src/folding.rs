@@ -0,0 +1,190 @@
+use unicode_normalization::char::{decompose_canonical, is_combining_mark};
+use crate::engine::{Error, Regex, RegexExt};
+use crate::enums::WordBounds;
+use crate::mapped::{transform_pattern, MappedCaptures, MappedMatch, MappedText};
+use crate::utils::{build_regex, build_whole_word_pattern};
+
+// Accent- and diacritic-insensitive matching.
+// Both the text and the pattern are folded before matching: characters are decomposed (NFD), combining marks
+// are removed and common ligatures and letters without decompositions are replaced, e.g. ß with ss, æ with ae
+// and ø with o, so café matches cafe and Zürich matches Zurich. Letter case is preserved, so folding combines
+// with the case_insensitive flag. Match offsets refer to the original text, so a match of cafe in café
+// ends after é. Only the literal characters of patterns are folded, so escapes and classes keep their meaning
+// and a character class only folds characters with a single-character folded form: [é] becomes [e], but [ß]
+// is kept and never matches, as ß is folded to ss in the text.
+
+/// Appends the folded form of a character
+fn fold_char(ch: char, output: &mut String) {
+  if ch.is_ascii() {
+    output.push(ch);
+    return;
+  }
+  let replacement = match ch {
+    'ß' => "ss",
+    'ẞ' => "SS",
+    'æ' => "ae",
+    'Æ' => "AE",
+    'œ' => "oe",
+    'Œ' => "OE",
+    'ø' => "o",
+    'Ø' => "O",
+    'đ' | 'ð' => "d",
+    'Đ' | 'Ð' => "D",
+    'ł' => "l",
+    'Ł' => "L",
+    'þ' => "th",
+    'Þ' => "TH",
+    'ı' => "i",
+    'ĳ' => "ij",
+    'Ĳ' => "IJ",
+    'ﬀ' => "ff",
+    'ﬁ' => "fi",
+    'ﬂ' => "fl",
+    _ => "",
+  };
+  if !replacement.is_empty() {
+    output.push_str(replacement);
+    return;
+  }
+  decompose_canonical(ch, |part| {
+    if !is_combining_mark(part) {
+      output.push(part);
+    }
+  });
+}
+
+/// Removes accents and diacritics and expands common ligatures, e.g. Crème Brûlée becomes Creme Brulee
+pub fn fold_accents(text: &str) -> String {
+  let mut output = String::with_capacity(text.len());
+  for ch in text.chars() {
+    fold_char(ch, &mut output);
+  }
+  output
+}
+
+impl<'a> MappedText<'a> {
+  /// Folds accents and ligatures as with fold_accents, mapping offsets back to the original text
+  pub fn folded(text: &'a str) -> MappedText<'a> {
+    let mut mapped = MappedText::with_capacity(text);
+    let mut folded_char = String::new();
+    for (start, ch) in text.char_indices() {
+      folded_char.clear();
+      fold_char(ch, &mut folded_char);
+      mapped.push(&folded_char, start, start + ch.len_utf8());
+    }
+    mapped
+  }
+}
+
+fn build_folded_regex(pattern: &str, case_insensitive: bool) -> Result<Regex, Error> {
+  build_regex(&transform_pattern(pattern, fold_accents), case_insensitive)
+}
+
+/// Accent-insensitive match methods for strings. Matches are returned with offsets in the original text.
+/// Only pattern_matches_folded_result needs to be implemented
+pub trait MatchFolded<'a> {
+  /// Yields all non-overlapping matches of the pattern after folding accents in both the text and pattern,
+  /// or an error if the pattern is not a valid regular expression
+  fn pattern_matches_folded_result(&'a self, pattern: &str, case_insensitive: bool) -> Result<Vec<MappedMatch<'a>>, Error>;
+
+  /// Yields all non-overlapping accent-insensitive matches, or an empty vector if the regex fails
+  fn pattern_matches_folded(&'a self, pattern: &str, case_insensitive: bool) -> Vec<MappedMatch<'a>> {
+    self.pattern_matches_folded_result(pattern, case_insensitive).unwrap_or_default()
+  }
+
+  /// Yields the first accent-insensitive match if any
+  fn pattern_first_match_folded(&'a self, pattern: &str, case_insensitive: bool) -> Option<MappedMatch<'a>> {
+    self.pattern_matches_folded(pattern, case_insensitive).into_iter().next()
+  }
+
+  /// Yields the capture groups of all non-overlapping matches after folding accents in both the text and pattern,
+  /// with offsets in the original text, or an error if the pattern is not a valid regular expression
+  fn pattern_captures_folded_result(&'a self, pattern: &str, case_insensitive: bool) -> Result<Vec<MappedCaptures<'a>>, Error> where Self: AsRef<str> {
+    let re = build_folded_regex(pattern, case_insensitive)?;
+    let folded = MappedText::folded(self.as_ref());
+    Ok(re.captures_all(folded.as_str()).map(|captures| folded.to_original_captures(&captures)).collect())
+  }
+
+  /// Yields the capture groups of all non-overlapping accent-insensitive matches, or an empty vector if the regex fails
+  fn pattern_captures_folded(&'a self, pattern: &str, case_insensitive: bool) -> Vec<MappedCaptures<'a>> where Self: AsRef<str> {
+    self.pattern_captures_folded_result(pattern, case_insensitive).unwrap_or_default()
+  }
+
+  /// Accent-insensitive match with a boolean case_insensitive flag. Returns false if the regex fails
+  fn pattern_match_folded(&'a self, pattern: &str, case_insensitive: bool) -> bool {
+    self.pattern_first_match_folded(pattern, case_insensitive).is_some()
+  }
+
+  /// Accent- and case-insensitive match
+  fn pattern_match_folded_ci(&'a self, pattern: &str) -> bool {
+    self.pattern_match_folded(pattern, true)
+  }
+
+  /// Accent-insensitive, case-sensitive match
+  fn pattern_match_folded_cs(&'a self, pattern: &str) -> bool {
+    self.pattern_match_folded(pattern, false)
+  }
+
+  /// Accent-insensitive word match with bounds and case_insensitive options
+  fn match_word_bounds_folded(&'a self, word: &str, bounds: WordBounds, case_insensitive: bool) -> bool {
+    self.pattern_match_folded(&bounds.to_pattern(word), case_insensitive)
+  }
+
+  /// Accent-insensitive whole word match with a boolean case_insensitive flag
+  fn match_word_folded(&'a self, word: &str, case_insensitive: bool) -> bool {
+    self.pattern_match_folded(&build_whole_word_pattern(word), case_insensitive)
+  }
+
+  /// Accent- and case-insensitive whole word match
+  fn match_word_folded_ci(&'a self, word: &str) -> bool {
+    self.match_word_folded(word, true)
+  }
+
+  /// Counts accent-insensitive whole word matches with a boolean case_insensitive flag
+  fn count_word_folded(&'a self, word: &str, case_insensitive: bool) -> usize {
+    self.pattern_matches_folded(&build_whole_word_pattern(word), case_insensitive).len()
+  }
+}
+
+impl<'a> MatchFolded<'a> for str {
+  fn pattern_matches_folded_result(&'a self, pattern: &str, case_insensitive: bool) -> Result<Vec<MappedMatch<'a>>, Error> {
+    let re = build_folded_regex(pattern, case_insensitive)?;
+    let folded = MappedText::folded(self);
+    Ok(re.find_all(folded.as_str()).map(|matched_item| folded.to_original_match(matched_item.start(), matched_item.end())).collect())
+  }
+}
+
+/// Methods to filter arrays or vectors of strings by accent-insensitive patterns compiled only once.
+/// If the regex fails, filters will not be applied
+pub trait FilterFolded<'a, T> {
+  /// Filters strings matching the pattern after folding accents with a case-insensitive flag
+  fn pattern_filter_folded(&'a self, pattern: &str, case_insensitive: bool) -> Vec<T>;
+
+  /// Filters strings by accent- and case-insensitive patterns
+  fn pattern_filter_folded_ci(&'a self, pattern: &str) -> Vec<T> {
+    self.pattern_filter_folded(pattern, true)
+  }
+
+  /// Filters strings by whole word patterns after folding accents with a case-insensitive flag
+  fn pattern_filter_word_folded(&'a self, word: &str, case_insensitive: bool) -> Vec<T> {
+    self.pattern_filter_folded(&build_whole_word_pattern(word), case_insensitive)
+  }
+}
+
+impl<'a> FilterFolded<'a, String> for [String] {
+  fn pattern_filter_folded(&'a self, pattern: &str, case_insensitive: bool) -> Vec<String> {
+    match build_folded_regex(pattern, case_insensitive) {
+      Ok(re) => self.iter().filter(|text| re.matches_text(&fold_accents(text))).cloned().collect(),
+      Err(_) => self.to_vec(),
+    }
+  }
+}
+
+impl<'a> FilterFolded<'a, &'a str> for [&'a str] {
+  fn pattern_filter_folded(&'a self, pattern: &str, case_insensitive: bool) -> Vec<&'a str> {
+    match build_folded_regex(pattern, case_insensitive) {
+      Ok(re) => self.iter().filter(|text| re.matches_text(&fold_accents(text))).copied().collect(),
+      Err(_) => self.to_vec(),
+    }
+  }
+}
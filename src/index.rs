@@ -0,0 +1,320 @@
+use std::collections::BTreeMap;
+use std::ops::Bound;
+use crate::engine::{Error, Regex, RegexExt};
+use crate::enums::WordBounds;
+use crate::query::{build_term_pattern, QueryError, QueryNode, QueryTerm, SearchQuery};
+use crate::utils::build_regex;
+
+// Inverted index for repeated word searches over large collections of strings.
+// Records are tokenized once into words (\w+ as with \b word boundaries) and each lower-case word maps to
+// the sorted ids of the records containing it. Queries look up candidate records from these postings lists
+// and only verify candidates with a regular expression where the postings alone cannot decide, e.g. for
+// case-sensitive matches, phrases or words with punctuation.
+
+/// How a word part is compared with indexed words
+enum PartMatcher {
+  Exact(String),
+  Prefix(String),
+  Suffix(String),
+  Contains(String),
+  Wildcard(Regex),
+}
+
+impl PartMatcher {
+  fn is_match(&self, key: &str) -> bool {
+    match self {
+      PartMatcher::Exact(part) => key == part,
+      PartMatcher::Prefix(part) => key.starts_with(part.as_str()),
+      PartMatcher::Suffix(part) => key.ends_with(part.as_str()),
+      PartMatcher::Contains(part) => key.contains(part.as_str()),
+      PartMatcher::Wildcard(re) => re.matches_text(key),
+    }
+  }
+}
+
+/// Inverted index of whole words in a collection of strings, borrowed for the lifetime of the index.
+/// Record ids are the indices of the records in the source array or vector.
+/// Lookups with whole-word, word start, word end or partial bounds and boolean search queries return sorted record ids
+pub struct WordIndex<'a> {
+  records: Vec<&'a str>,
+  postings: BTreeMap<String, Vec<usize>>,
+  word_regex: Regex,
+  part_regex: Regex,
+}
+
+impl<'a> WordIndex<'a> {
+
+  /// Tokenizes all records and builds the postings lists
+  pub fn new<T: AsRef<str>>(records: &'a [T]) -> WordIndex<'a> {
+    let records: Vec<&'a str> = records.iter().map(|record| record.as_ref()).collect();
+    let word_regex = build_regex(r#"\w+"#, false).expect("valid word pattern");
+    let part_regex = build_regex(r#"[\w*?]+"#, false).expect("valid word part pattern");
+    let mut postings: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (id, record) in records.iter().enumerate() {
+      for word in word_regex.find_all(record) {
+        let ids = postings.entry(word.as_str().to_lowercase()).or_default();
+        if ids.last() != Some(&id) {
+          ids.push(id);
+        }
+      }
+    }
+    WordIndex { records, postings, word_regex, part_regex }
+  }
+
+  /// Number of indexed records
+  pub fn len(&self) -> usize {
+    self.records.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.records.is_empty()
+  }
+
+  /// Number of distinct lower-case words
+  pub fn num_words(&self) -> usize {
+    self.postings.len()
+  }
+
+  /// Returns the record with the given id
+  pub fn record(&self, id: usize) -> Option<&'a str> {
+    self.records.get(id).copied()
+  }
+
+  /// Returns the records for a vector of record ids
+  pub fn records(&self, ids: &[usize]) -> Vec<&'a str> {
+    ids.iter().filter_map(|id| self.record(*id)).collect()
+  }
+
+  /// Ids of records containing the lower-case form of a word
+  pub fn postings(&self, word: &str) -> &[usize] {
+    self.postings.get(&word.to_lowercase()).map_or(&[], |ids| ids.as_slice())
+  }
+
+  /// Union of the postings lists of all indexed words accepted by the matcher
+  fn matching_ids(&self, matcher: &PartMatcher) -> Vec<usize> {
+    match matcher {
+      PartMatcher::Exact(part) => self.postings.get(part).cloned().unwrap_or_default(),
+      PartMatcher::Prefix(part) => {
+        let lists = self.postings.range::<str, _>((Bound::Included(part.as_str()), Bound::Unbounded))
+          .take_while(|(key, _)| key.starts_with(part.as_str()))
+          .map(|(_, ids)| ids.as_slice());
+        union_all(lists)
+      },
+      _ => union_all(self.postings.iter().filter(|(key, _)| matcher.is_match(key)).map(|(_, ids)| ids.as_slice())),
+    }
+  }
+
+  /// Candidate ids containing all word parts of a word, e.g. hip and hop for hip-hop.
+  /// The first and last parts may be partial words depending on the bounds, while inner parts are whole words
+  fn candidate_ids(&self, word: &str, has_start: bool, has_end: bool) -> Result<Option<Vec<usize>>, Error> {
+    let parts: Vec<&str> = self.part_regex.find_all(word).map(|part| part.as_str()).collect();
+    if parts.is_empty() {
+      return Ok(None);
+    }
+    let last_index = parts.len() - 1;
+    let mut candidates: Option<Vec<usize>> = None;
+    for (index, part) in parts.iter().enumerate() {
+      let exact_start = index > 0 || has_start || !word.starts_with(*part);
+      let exact_end = index < last_index || has_end || !word.ends_with(*part);
+      let matcher = if part.contains(['*', '?']) {
+        // indexed words are lower case, so case-sensitive wildcards are checked when verifying candidates
+        let pattern = [if exact_start { "^" } else { "" }, &build_term_pattern(part), if exact_end { "$" } else { "" }].concat();
+        PartMatcher::Wildcard(build_regex(&pattern, true)?)
+      } else {
+        let part = part.to_lowercase();
+        match (exact_start, exact_end) {
+          (true, true) => PartMatcher::Exact(part),
+          (true, false) => PartMatcher::Prefix(part),
+          (false, true) => PartMatcher::Suffix(part),
+          (false, false) => PartMatcher::Contains(part),
+        }
+      };
+      let ids = self.matching_ids(&matcher);
+      let ids = match candidates {
+        Some(previous) => intersect(&previous, &ids),
+        None => ids,
+      };
+      if ids.is_empty() {
+        return Ok(Some(ids));
+      }
+      candidates = Some(ids);
+    }
+    Ok(candidates)
+  }
+
+  /// Verify candidate records with a regular expression. Without candidates, all records are verified
+  fn verify(&self, candidates: Option<Vec<usize>>, re: &Regex) -> Vec<usize> {
+    match candidates {
+      Some(ids) => ids.into_iter().filter(|id| re.matches_text(self.records[*id])).collect(),
+      None => (0..self.records.len()).filter(|id| re.matches_text(self.records[*id])).collect(),
+    }
+  }
+
+  /// Postings are exact for case-insensitive lookups of a single ASCII word part
+  fn is_exact_lookup(&self, word: &str, case_insensitive: bool) -> bool {
+    case_insensitive && word.is_ascii() && self.word_regex.find_first(word).is_some_and(|part| part.as_str() == word)
+  }
+
+  /// Ids of records containing a literal word with bounds options and case_insensitive flag, as in MatchWord::match_word_bounds.
+  /// Unlike MatchWord methods, the word is not a regular expression, except that * and ? may be used as wildcards within words
+  pub fn find_word_bounds_result(&self, word: &str, bounds: WordBounds, case_insensitive: bool) -> Result<Vec<usize>, Error> {
+    let (has_start, has_end) = match bounds {
+      WordBounds::None => (false, false),
+      WordBounds::Start => (true, false),
+      WordBounds::End => (false, true),
+      WordBounds::Both => (true, true),
+    };
+    let candidates = self.candidate_ids(word, has_start, has_end)?;
+    if self.is_exact_lookup(word, case_insensitive) && !word.contains(['*', '?']) {
+      return Ok(candidates.unwrap_or_default());
+    }
+    let pattern = bounds.to_pattern(&build_term_pattern(word));
+    let re = build_regex(&pattern, case_insensitive)?;
+    Ok(self.verify(candidates, &re))
+  }
+
+  /// Ids of records containing a literal word with bounds options. Returns an empty vector if the word cannot be compiled
+  pub fn find_word_bounds(&self, word: &str, bounds: WordBounds, case_insensitive: bool) -> Vec<usize> {
+    self.find_word_bounds_result(word, bounds, case_insensitive).unwrap_or_default()
+  }
+
+  /// Ids of records containing a whole word
+  pub fn find_word(&self, word: &str, case_insensitive: bool) -> Vec<usize> {
+    self.find_word_bounds(word, WordBounds::Both, case_insensitive)
+  }
+
+  /// Ids of records containing a word starting with the prefix
+  pub fn find_word_start(&self, prefix: &str, case_insensitive: bool) -> Vec<usize> {
+    self.find_word_bounds(prefix, WordBounds::Start, case_insensitive)
+  }
+
+  /// Ids of records containing a word ending with the suffix
+  pub fn find_word_end(&self, suffix: &str, case_insensitive: bool) -> Vec<usize> {
+    self.find_word_bounds(suffix, WordBounds::End, case_insensitive)
+  }
+
+  /// Ids of records containing all whole words
+  pub fn find_words(&self, words: &[&str], case_insensitive: bool) -> Vec<usize> {
+    let mut ids: Option<Vec<usize>> = None;
+    for word in words {
+      let word_ids = self.find_word(word, case_insensitive);
+      let word_ids = match ids {
+        Some(previous) => intersect(&previous, &word_ids),
+        None => word_ids,
+      };
+      if word_ids.is_empty() {
+        return word_ids;
+      }
+      ids = Some(word_ids);
+    }
+    ids.unwrap_or_default()
+  }
+
+  /// Ids of records containing any of the whole words
+  pub fn find_any_words(&self, words: &[&str], case_insensitive: bool) -> Vec<usize> {
+    let lists: Vec<Vec<usize>> = words.iter().map(|word| self.find_word(word, case_insensitive)).collect();
+    union_all(lists.iter().map(|ids| ids.as_slice()))
+  }
+
+  /// Ids of records matching a query term
+  fn find_term(&self, term: &QueryTerm, case_insensitive: bool) -> Vec<usize> {
+    let words = term.words();
+    if let [word] = words {
+      if self.is_exact_lookup(word, case_insensitive) {
+        return self.postings(word).to_vec();
+      }
+    }
+    let mut candidates: Option<Vec<usize>> = None;
+    for word in words {
+      // a regex error cannot occur for escaped words with wildcards, but falls back to verifying all records
+      let ids = self.candidate_ids(word, true, true).ok().flatten();
+      candidates = match (candidates, ids) {
+        (Some(previous), Some(ids)) => Some(intersect(&previous, &ids)),
+        (previous, ids) => previous.or(ids),
+      };
+    }
+    let has_exact_wildcards = case_insensitive && words.len() == 1 && words[0].chars().all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '*' | '?'));
+    if has_exact_wildcards {
+      return candidates.unwrap_or_default();
+    }
+    self.verify(candidates, term.regex())
+  }
+
+  /// Evaluates a query node with postings set operations
+  fn find_node(&self, node: &QueryNode, case_insensitive: bool) -> Vec<usize> {
+    match node {
+      QueryNode::Term(term) => self.find_term(term, case_insensitive),
+      QueryNode::And(nodes) => {
+        let mut ids: Option<Vec<usize>> = None;
+        for node in nodes {
+          let node_ids = self.find_node(node, case_insensitive);
+          ids = Some(match ids {
+            Some(previous) => intersect(&previous, &node_ids),
+            None => node_ids,
+          });
+        }
+        ids.unwrap_or_default()
+      },
+      QueryNode::Or(nodes) => {
+        let lists: Vec<Vec<usize>> = nodes.iter().map(|node| self.find_node(node, case_insensitive)).collect();
+        union_all(lists.iter().map(|ids| ids.as_slice()))
+      },
+      QueryNode::Not(node) => complement(&self.find_node(node, case_insensitive), self.records.len()),
+    }
+  }
+
+  /// Ids of records matching a compiled search query
+  pub fn search(&self, search_query: &SearchQuery) -> Vec<usize> {
+    self.find_node(search_query.root(), search_query.is_case_insensitive())
+  }
+
+  /// Ids of records matching a search query string or a QueryError if it cannot be parsed
+  pub fn search_result(&self, query: &str, case_insensitive: bool) -> Result<Vec<usize>, QueryError> {
+    SearchQuery::new(query, case_insensitive).map(|search_query| self.search(&search_query))
+  }
+
+  /// Records containing a whole word
+  pub fn filter_word(&self, word: &str, case_insensitive: bool) -> Vec<&'a str> {
+    self.records(&self.find_word(word, case_insensitive))
+  }
+
+  /// Records matching a search query string. Returns an empty vector if it cannot be parsed
+  pub fn filter_query(&self, query: &str, case_insensitive: bool) -> Vec<&'a str> {
+    self.search_result(query, case_insensitive).map(|ids| self.records(&ids)).unwrap_or_default()
+  }
+}
+
+/// Intersection of two sorted id lists
+fn intersect(first: &[usize], second: &[usize]) -> Vec<usize> {
+  let mut ids = Vec::with_capacity(first.len().min(second.len()));
+  let (mut i, mut j) = (0, 0);
+  while i < first.len() && j < second.len() {
+    if first[i] < second[j] {
+      i += 1;
+    } else if first[i] > second[j] {
+      j += 1;
+    } else {
+      ids.push(first[i]);
+      i += 1;
+      j += 1;
+    }
+  }
+  ids
+}
+
+/// Sorted union of any number of sorted id lists
+fn union_all<'b>(lists: impl Iterator<Item = &'b [usize]>) -> Vec<usize> {
+  let mut ids: Vec<usize> = lists.flatten().copied().collect();
+  ids.sort_unstable();
+  ids.dedup();
+  ids
+}
+
+/// Ids from 0 to len not in the sorted id list
+fn complement(ids: &[usize], len: usize) -> Vec<usize> {
+  let mut excluded = ids.iter().peekable();
+  (0..len).filter(|id| {
+    while excluded.next_if(|excluded_id| **excluded_id < *id).is_some() {}
+    excluded.peek() != Some(&id)
+  }).collect()
+}
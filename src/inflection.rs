@@ -0,0 +1,204 @@
+use crate::engine::{Error, RegexExt};
+use crate::enums::{Inflection, WordBounds};
+use crate::utils::{build_regex, build_word_list_pattern};
+
+// English inflection variants for whole word matching and replacement.
+// A base word is expanded into its plural or third person singular, -ing, past tense and past participle forms
+// with common spelling rules (boxes, cities, making, stopped, tried) and tables of irregular nouns and verbs
+// (mice, children, ran, written). The variants are compiled into a single prefix-trie pattern. Words are
+// expected in their base form and all variants are generated, so nouns also match verb forms such as
+// cats and catting. Only the trailing run of letters is inflected, so compounds such as ice-cream or
+// phrases such as tin can inflect their last word.
+
+/// Irregular and unchanged noun plurals and irregular third person singular verbs
+const IRREGULAR_PLURALS: [(&str, &str); 45] = [
+  ("aircraft", "aircraft"), ("analysis", "analyses"), ("cactus", "cacti"), ("calf", "calves"),
+  ("child", "children"), ("crisis", "crises"), ("criterion", "criteria"), ("datum", "data"),
+  ("deer", "deer"), ("echo", "echoes"), ("fish", "fish"), ("foot", "feet"),
+  ("fungus", "fungi"), ("goose", "geese"), ("half", "halves"), ("hero", "heroes"),
+  ("knife", "knives"), ("leaf", "leaves"), ("life", "lives"), ("loaf", "loaves"),
+  ("louse", "lice"), ("man", "men"), ("mouse", "mice"), ("nucleus", "nuclei"),
+  ("ox", "oxen"), ("person", "people"), ("phenomenon", "phenomena"), ("potato", "potatoes"),
+  ("series", "series"), ("sheep", "sheep"), ("shelf", "shelves"), ("species", "species"),
+  ("thesis", "theses"), ("thief", "thieves"), ("tomato", "tomatoes"), ("tooth", "teeth"),
+  ("veto", "vetoes"), ("wife", "wives"), ("wolf", "wolves"), ("woman", "women"),
+  ("quiz", "quizzes"), ("be", "is"), ("do", "does"), ("go", "goes"), ("have", "has"),
+];
+
+/// Irregular verbs with their simple past and past participle
+const IRREGULAR_VERBS: [(&str, &str, &str); 60] = [
+  ("be", "was", "been"), ("begin", "began", "begun"), ("break", "broke", "broken"), ("bring", "brought", "brought"),
+  ("build", "built", "built"), ("buy", "bought", "bought"), ("catch", "caught", "caught"), ("choose", "chose", "chosen"),
+  ("come", "came", "come"), ("cost", "cost", "cost"), ("cut", "cut", "cut"), ("do", "did", "done"),
+  ("draw", "drew", "drawn"), ("drink", "drank", "drunk"), ("drive", "drove", "driven"), ("eat", "ate", "eaten"),
+  ("fall", "fell", "fallen"), ("feel", "felt", "felt"), ("fight", "fought", "fought"), ("find", "found", "found"),
+  ("fly", "flew", "flown"), ("forget", "forgot", "forgotten"), ("get", "got", "got"), ("give", "gave", "given"),
+  ("go", "went", "gone"), ("grow", "grew", "grown"), ("have", "had", "had"), ("hit", "hit", "hit"),
+  ("hold", "held", "held"), ("keep", "kept", "kept"), ("know", "knew", "known"), ("lead", "led", "led"),
+  ("leave", "left", "left"), ("let", "let", "let"), ("lose", "lost", "lost"), ("make", "made", "made"),
+  ("meet", "met", "met"), ("pay", "paid", "paid"), ("put", "put", "put"), ("read", "read", "read"),
+  ("ride", "rode", "ridden"), ("run", "ran", "run"), ("say", "said", "said"), ("see", "saw", "seen"),
+  ("sell", "sold", "sold"), ("send", "sent", "sent"), ("set", "set", "set"), ("sing", "sang", "sung"),
+  ("sit", "sat", "sat"), ("sleep", "slept", "slept"), ("speak", "spoke", "spoken"), ("spend", "spent", "spent"),
+  ("stand", "stood", "stood"), ("swim", "swam", "swum"), ("take", "took", "taken"), ("teach", "taught", "taught"),
+  ("tell", "told", "told"), ("think", "thought", "thought"), ("win", "won", "won"), ("write", "wrote", "written"),
+];
+
+/// Words of several syllables stressed on the last one, which double their final consonant, e.g. beginning
+const FINAL_STRESS_WORDS: [&str; 24] = [
+  "admit", "begin", "commit", "compel", "control", "equip", "expel", "forbid", "forget", "incur", "occur", "omit",
+  "patrol", "permit", "prefer", "propel", "rebel", "recur", "refer", "regret", "submit", "transfer", "upset", "worship",
+];
+
+fn is_vowel(ch: char) -> bool {
+  matches!(ch, 'a' | 'e' | 'i' | 'o' | 'u')
+}
+
+/// Whether a word ending in a consonant preceded by a single vowel doubles the consonant before -ing and -ed,
+/// e.g. run and stop. Only words with one vowel group or stressed on the last syllable are doubled,
+/// so open and visit are not, but begin and forget are
+fn doubles_final_consonant(word: &str) -> bool {
+  let chars: Vec<char> = word.chars().collect();
+  // u after q is a glide rather than a vowel, as in quiz and quit
+  let vowels: Vec<bool> = chars.iter().enumerate().map(|(index, ch)| is_vowel(*ch) && !(*ch == 'u' && index > 0 && chars[index - 1] == 'q')).collect();
+  let [.., before_vowel, vowel, last] = vowels[..] else {
+    return false;
+  };
+  let vowel_groups = vowels.iter().enumerate().filter(|(index, is_vowel)| **is_vowel && (*index == 0 || !vowels[index - 1])).count();
+  let last_char = chars[chars.len() - 1];
+  !last && !matches!(last_char, 'w' | 'x' | 'y') && vowel && !before_vowel && (vowel_groups == 1 || FINAL_STRESS_WORDS.contains(&word))
+}
+
+/// Appends the suffix to the word, doubling its final consonant if needed
+fn append_suffix(word: &str, suffix: &str) -> String {
+  let mut output = word.to_owned();
+  if doubles_final_consonant(word) {
+    if let Some(last) = word.chars().last() {
+      output.push(last);
+    }
+  }
+  output.push_str(suffix);
+  output
+}
+
+/// Whether the word ends in y after a consonant, e.g. city or try
+fn ends_with_consonant_y(word: &str) -> bool {
+  let mut chars = word.chars().rev();
+  chars.next() == Some('y') && chars.next().is_some_and(|ch| !is_vowel(ch))
+}
+
+fn pluralize(word: &str) -> String {
+  if let Some((_, plural)) = IRREGULAR_PLURALS.iter().find(|(singular, _)| *singular == word) {
+    return plural.to_string();
+  }
+  if ends_with_consonant_y(word) {
+    return [&word[..word.len() - 1], "ies"].concat();
+  }
+  if ["s", "x", "z", "ch", "sh"].iter().any(|suffix| word.ends_with(suffix)) {
+    return [word, "es"].concat();
+  }
+  [word, "s"].concat()
+}
+
+fn present_participle(word: &str) -> String {
+  if let Some(stem) = word.strip_suffix("ie") {
+    return [stem, "ying"].concat();
+  }
+  if word.len() > 2 && word.ends_with('e') && !["ee", "oe", "ye"].iter().any(|suffix| word.ends_with(suffix)) {
+    return [&word[..word.len() - 1], "ing"].concat();
+  }
+  append_suffix(word, "ing")
+}
+
+fn regular_past(word: &str) -> String {
+  if word.ends_with('e') {
+    return [word, "d"].concat();
+  }
+  if ends_with_consonant_y(word) {
+    return [&word[..word.len() - 1], "ied"].concat();
+  }
+  append_suffix(word, "ed")
+}
+
+/// Applies the capitalisation of the source, i.e. all capitals or an initial capital, to a lower-case word
+fn transfer_case(source: &str, word: &str) -> String {
+  let mut letters = source.chars().filter(|ch| ch.is_alphabetic());
+  let Some(first) = letters.next() else {
+    return word.to_owned();
+  };
+  let rest: Vec<char> = letters.collect();
+  if first.is_uppercase() && !rest.is_empty() && rest.iter().all(|ch| ch.is_uppercase()) {
+    word.to_uppercase()
+  } else if first.is_uppercase() {
+    let mut chars = word.chars();
+    chars.next().map(|ch| ch.to_uppercase().chain(chars).collect()).unwrap_or_default()
+  } else {
+    word.to_owned()
+  }
+}
+
+/// Generates all inflections of a word, including those with the same spelling
+fn inflection_forms(word: &str) -> Vec<(Inflection, String)> {
+  let stem_start = word.char_indices().rev().take_while(|(_, ch)| ch.is_alphabetic()).last().map(|(index, _)| index).unwrap_or(word.len());
+  let (prefix, stem) = word.split_at(stem_start);
+  if stem.is_empty() {
+    return vec![(Inflection::Base, word.to_owned())];
+  }
+  let base = stem.to_lowercase();
+  let (past, past_participle) = match IRREGULAR_VERBS.iter().find(|(verb, _, _)| *verb == base) {
+    Some((_, past, participle)) => (past.to_string(), participle.to_string()),
+    None => (regular_past(&base), regular_past(&base)),
+  };
+  vec![
+    (Inflection::Base, base.clone()),
+    (Inflection::Plural, pluralize(&base)),
+    (Inflection::PresentParticiple, present_participle(&base)),
+    (Inflection::Past, past),
+    (Inflection::PastParticiple, past_participle),
+  ].into_iter().map(|(inflection, form)| (inflection, [prefix, &transfer_case(stem, &form)].concat())).collect()
+}
+
+/// Generates the English inflections of a word in its base form, e.g. mouse, mice, mousing, moused.
+/// Variants with the same spelling as an earlier inflection are omitted, and the capitalisation of the word is kept
+pub fn inflect_word(word: &str) -> Vec<(Inflection, String)> {
+  let mut variants: Vec<(Inflection, String)> = vec![];
+  for (inflection, variant) in inflection_forms(word) {
+    if variants.iter().all(|(_, existing)| *existing != variant) {
+      variants.push((inflection, variant));
+    }
+  }
+  variants
+}
+
+/// Builds a whole word pattern matching any English inflection of the word, with regex meta characters escaped
+pub fn build_inflected_word_pattern(word: &str) -> String {
+  let variants = inflect_word(word);
+  let variant_strs: Vec<&str> = variants.iter().map(|(_, variant)| variant.as_str()).collect();
+  build_word_list_pattern(&variant_strs, WordBounds::Both)
+}
+
+/// Replaces each inflection of the word with the same inflection of the replacement, e.g. mice with rats
+/// when replacing mouse with rat. A lower-case replacement takes the capitalisation of the matched word
+pub(crate) fn replace_inflected_words(text: &str, word: &str, replacement: &str, case_insensitive: bool) -> Result<String, Error> {
+  let re = build_regex(&build_inflected_word_pattern(word), case_insensitive)?;
+  let variants = inflect_word(&word.to_lowercase());
+  let replacements = inflection_forms(replacement);
+  let keep_case = replacement.chars().any(|ch| ch.is_uppercase());
+  let mut output = String::with_capacity(text.len());
+  let mut position = 0;
+  for matched_item in re.find_all(text) {
+    let matched_text = matched_item.as_str();
+    let lower_text = matched_text.to_lowercase();
+    let inflection = variants.iter().find(|(_, variant)| *variant == lower_text).map(|(inflection, _)| *inflection).unwrap_or(Inflection::Base);
+    let replacement_variant = replacements.iter().find(|(other, _)| *other == inflection).map(|(_, variant)| variant.as_str()).unwrap_or(replacement);
+    output.push_str(&text[position..matched_item.start()]);
+    if keep_case {
+      output.push_str(replacement_variant);
+    } else {
+      output.push_str(&transfer_case(matched_text, replacement_variant));
+    }
+    position = matched_item.end();
+  }
+  output.push_str(&text[position..]);
+  Ok(output)
+}
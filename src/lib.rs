@@ -1,6 +1,8 @@
-extern crate regex;
-
+mod engine;
+mod prefilter;
 mod utils;
+#[cfg(any(feature = "folding", feature = "normalization"))]
+mod mapped;
 pub mod enums;
 pub mod pattern_match;
 pub mod pattern_replace;
@@ -9,6 +11,26 @@ pub mod pattern_many;
 pub mod pattern_split;
 pub mod pattern_capture;
 pub mod words;
+pub mod positions;
+pub mod pattern_lookaround;
+pub mod query;
+pub mod scoring;
+pub mod index;
+pub mod boundary;
+pub mod dialects;
+pub mod inflection;
+#[cfg(feature = "dictionary")]
+pub mod dictionary;
+#[cfg(feature = "segmentation")]
+pub mod segmentation;
+#[cfg(feature = "folding")]
+pub mod folding;
+#[cfg(feature = "normalization")]
+pub mod normalization;
+#[cfg(feature = "stemming")]
+pub mod stemming;
+#[cfg(feature = "backreferences")]
+pub mod backreferences;
 
 /// This library provides a set of traits and extension methods for &str and/or String
 /// to facilitate common string manipulation routines that may require multiple steps
@@ -31,5 +53,27 @@ pub use crate::pattern_many::*;
 pub use crate::pattern_split::*;
 pub use crate::pattern_capture::*;
 pub use crate::words::*;
-pub use crate::utils::build_regex;
-pub use regex::{Error, Regex, Captures, Match};
\ No newline at end of file
+pub use crate::positions::*;
+pub use crate::pattern_lookaround::*;
+pub use crate::query::*;
+pub use crate::scoring::*;
+pub use crate::index::*;
+pub use crate::boundary::*;
+pub use crate::dialects::*;
+pub use crate::inflection::*;
+#[cfg(feature = "dictionary")]
+pub use crate::dictionary::*;
+#[cfg(feature = "segmentation")]
+pub use crate::segmentation::*;
+#[cfg(feature = "folding")]
+pub use crate::folding::*;
+#[cfg(feature = "normalization")]
+pub use crate::normalization::*;
+#[cfg(feature = "stemming")]
+pub use crate::stemming::*;
+#[cfg(any(feature = "folding", feature = "normalization"))]
+pub use crate::mapped::{MappedCaptures, MappedMatch, MappedText};
+#[cfg(feature = "backreferences")]
+pub use crate::backreferences::*;
+pub use crate::utils::{build_regex, build_flex_space_pattern, build_word_list_pattern};
+pub use crate::engine::{Error, Regex, Captures, Match, backend_name};
\ No newline at end of file
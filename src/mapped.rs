@@ -0,0 +1,152 @@
+// Transformed copies of texts for matching, e.g. with accents folded or in a Unicode normalization form.
+// Each byte of the transformed text records the byte range of the original characters it derives from,
+// so that matches in the transformed text can be reported with offsets and slices of the original text.
+// Patterns are transformed only in their literal characters, so escapes, character classes and other syntax
+// keep their meaning. Within a class, a character is only replaced if it transforms to a single character,
+// so [ß] stays as it is when folding and [é] stays precomposed in NFD and never matches decomposed text.
+
+use unicode_normalization::char::is_combining_mark;
+use crate::engine::{escape_literal, Captures};
+use crate::utils::{escape_class_char, map_pattern_literals};
+
+/// Match within an original text, found by matching a transformed copy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MappedMatch<'a> {
+  start: usize,
+  end: usize,
+  text: &'a str,
+}
+
+impl<'a> MappedMatch<'a> {
+  /// Byte offset of the start of the match in the original text
+  pub fn start(&self) -> usize {
+    self.start
+  }
+
+  /// Byte offset of the end of the match in the original text
+  pub fn end(&self) -> usize {
+    self.end
+  }
+
+  /// Returns the matched slice of the original text
+  pub fn as_str(&self) -> &'a str {
+    self.text
+  }
+}
+
+/// Capture groups of a match within an original text, found by matching a transformed copy. Group 0 is the whole match
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MappedCaptures<'a> {
+  groups: Vec<Option<MappedMatch<'a>>>,
+}
+
+impl<'a> MappedCaptures<'a> {
+  /// Returns the capture group with the given index if it participated in the match
+  pub fn get(&self, index: usize) -> Option<MappedMatch<'a>> {
+    self.groups.get(index).copied().flatten()
+  }
+
+  /// Number of capture groups including group 0, whether they participated in the match or not
+  pub fn len(&self) -> usize {
+    self.groups.len()
+  }
+
+  /// Always false, as group 0 is always present
+  pub fn is_empty(&self) -> bool {
+    self.groups.is_empty()
+  }
+
+  /// Iterates over all capture groups in order, with None for groups that did not participate
+  pub fn iter(&self) -> impl Iterator<Item = Option<MappedMatch<'a>>> + '_ {
+    self.groups.iter().copied()
+  }
+}
+
+/// Transformed copy of a text with a map from each of its bytes back to the original text
+#[derive(Debug, Clone)]
+pub struct MappedText<'a> {
+  original: &'a str,
+  text: String,
+  origins: Vec<(usize, usize)>,
+}
+
+impl<'a> MappedText<'a> {
+  pub(crate) fn with_capacity(original: &'a str) -> MappedText<'a> {
+    MappedText { original, text: String::with_capacity(original.len()), origins: Vec::with_capacity(original.len()) }
+  }
+
+  /// Appends the transformed form of the original characters between the start and end byte offsets.
+  /// If they are removed, e.g. combining marks, the preceding transformed character extends over them
+  pub(crate) fn push(&mut self, transformed: &str, start: usize, end: usize) {
+    if transformed.is_empty() {
+      if let Some(&(previous_start, _)) = self.origins.last() {
+        for origin in self.origins.iter_mut().rev().take_while(|origin| origin.0 == previous_start) {
+          origin.1 = end;
+        }
+      }
+      return;
+    }
+    self.text.push_str(transformed);
+    self.origins.extend(std::iter::repeat((start, end)).take(transformed.len()));
+  }
+
+  /// Returns the transformed text
+  pub fn as_str(&self) -> &str {
+    &self.text
+  }
+
+  /// Returns the original text
+  pub fn original(&self) -> &'a str {
+    self.original
+  }
+
+  /// Converts a byte range in the transformed text to the smallest range in the original text covering
+  /// all characters it derives from
+  pub fn to_original_range(&self, start: usize, end: usize) -> (usize, usize) {
+    let original_start = self.origins.get(start).map(|origin| origin.0).unwrap_or(self.original.len());
+    if end <= start {
+      return (original_start, original_start);
+    }
+    let original_end = self.origins.get(end - 1).map(|origin| origin.1).unwrap_or(self.original.len());
+    (original_start, original_end)
+  }
+
+  /// Converts a byte range in the transformed text to a match in the original text
+  pub fn to_original_match(&self, start: usize, end: usize) -> MappedMatch<'a> {
+    let (start, end) = self.to_original_range(start, end);
+    MappedMatch { start, end, text: &self.original[start..end] }
+  }
+
+  /// Converts the capture groups of a match in the transformed text to capture groups in the original text
+  pub fn to_original_captures(&self, captures: &Captures) -> MappedCaptures<'a> {
+    let groups = captures.iter().map(|group| group.map(|matched_item| self.to_original_match(matched_item.start(), matched_item.end()))).collect();
+    MappedCaptures { groups }
+  }
+}
+
+/// Transforms the literal characters of a pattern, escaping the result. A quantifier after a character
+/// transformed to several characters, e.g. ß folded to ss, applies to all of them
+pub(crate) fn transform_pattern(pattern: &str, transform: impl Fn(&str) -> String) -> String {
+  map_pattern_literals(pattern, |literal, quantified| {
+    if literal.is_ascii() {
+      return literal.to_owned();
+    }
+    // a quantifier applies to the last base character with its combining marks
+    let last_start = literal.char_indices().rev().find(|(_, ch)| !is_combining_mark(*ch)).map(|(index, _)| index).unwrap_or(0);
+    let (head, last) = literal.split_at(last_start);
+    let transformed_last = transform(last);
+    let escaped_last = escape_literal(&transformed_last);
+    if quantified && transformed_last.chars().count() > 1 {
+      [escape_literal(&transform(head)), "(?:".to_owned(), escaped_last, ")".to_owned()].concat()
+    } else {
+      [escape_literal(&transform(head)), escaped_last].concat()
+    }
+  }, |ch| {
+    let transformed = transform(&ch.to_string());
+    let mut chars = transformed.chars();
+    match (chars.next(), chars.next()) {
+      (Some(single), None) if single != ch => Some(escape_class_char(single)),
+      _ => None,
+    }
+  })
+}
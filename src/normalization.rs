@@ -0,0 +1,212 @@
+use unicode_normalization::char::canonical_combining_class;
+use unicode_normalization::UnicodeNormalization;
+use crate::engine::{Error, Regex, RegexExt};
+use crate::mapped::{transform_pattern, MappedCaptures, MappedMatch, MappedText};
+use crate::utils::{build_regex, build_whole_word_pattern};
+
+// Unicode normalization-aware matching and replacement.
+// The same text may be encoded with precomposed characters (NFC) or with base letters followed by combining
+// marks (NFD), so a pattern in one form silently fails to match text in the other. These methods normalize
+// both the text and the pattern to the same form before matching. Only the literal characters of patterns are
+// normalized and escaped, so a fullwidth asterisk becomes a literal * with NFKC rather than a quantifier, and
+// classes only replace characters normalizing to a single character, so [é] is kept as it is with NFD.
+// The text is normalized in runs of characters that normalize independently, usually a base character with
+// its combining marks, so match offsets can be mapped back to whole characters of the original text.
+
+/// Unicode normalization forms as defined by UAX #15
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalizationForm {
+  /// Canonical composition, e.g. e followed by a combining acute accent becomes é
+  Nfc,
+  /// Canonical decomposition, e.g. é becomes e followed by a combining acute accent
+  Nfd,
+  /// Compatibility composition, e.g. the ligature ﬁ becomes fi and fullwidth Ａ becomes A
+  Nfkc,
+  /// Compatibility decomposition
+  Nfkd,
+}
+
+impl NormalizationForm {
+  /// Returns the text in this normalization form
+  pub fn normalize(&self, text: &str) -> String {
+    match self {
+      NormalizationForm::Nfc => text.nfc().collect(),
+      NormalizationForm::Nfd => text.nfd().collect(),
+      NormalizationForm::Nfkc => text.nfkc().collect(),
+      NormalizationForm::Nfkd => text.nfkd().collect(),
+    }
+  }
+}
+
+/// Splits the text into runs starting with a character of combining class 0, i.e. not a combining mark
+fn starter_runs(text: &str) -> Vec<(usize, usize)> {
+  let mut runs: Vec<(usize, usize)> = vec![];
+  let mut run_start = 0;
+  for (index, ch) in text.char_indices() {
+    if index > run_start && canonical_combining_class(ch) == 0 {
+      runs.push((run_start, index));
+      run_start = index;
+    }
+  }
+  if run_start < text.len() {
+    runs.push((run_start, text.len()));
+  }
+  runs
+}
+
+impl<'a> MappedText<'a> {
+  /// Normalizes the text to the given form, mapping offsets back to the original text.
+  /// Adjacent runs that compose with each other, such as Hangul jamo, are normalized together
+  pub fn normalized(text: &'a str, form: NormalizationForm) -> MappedText<'a> {
+    let mut mapped = MappedText::with_capacity(text);
+    let mut group: Option<(usize, usize, String)> = None;
+    for (start, end) in starter_runs(text) {
+      let normalized_run = form.normalize(&text[start..end]);
+      group = match group {
+        Some((group_start, _, normalized_group)) => {
+          let joined = form.normalize(&text[group_start..end]);
+          if joined.len() == normalized_group.len() + normalized_run.len() && joined.starts_with(&normalized_group) && joined.ends_with(&normalized_run) {
+            mapped.push(&normalized_group, group_start, start);
+            Some((start, end, normalized_run))
+          } else {
+            Some((group_start, end, joined))
+          }
+        },
+        None => Some((start, end, normalized_run)),
+      };
+    }
+    if let Some((group_start, group_end, normalized_group)) = group {
+      mapped.push(&normalized_group, group_start, group_end);
+    }
+    mapped
+  }
+}
+
+fn build_normalized_regex(pattern: &str, form: NormalizationForm, case_insensitive: bool) -> Result<Regex, Error> {
+  build_regex(&transform_pattern(pattern, |literal| form.normalize(literal)), case_insensitive)
+}
+
+/// Match methods that normalize both the text and the pattern to the same Unicode normalization form.
+/// Matches are returned with offsets in the original text. Only pattern_matches_normalized_result needs to be implemented
+pub trait MatchNormalized<'a> {
+  /// Yields all non-overlapping matches after normalizing the text and pattern,
+  /// or an error if the pattern is not a valid regular expression
+  fn pattern_matches_normalized_result(&'a self, pattern: &str, form: NormalizationForm, case_insensitive: bool) -> Result<Vec<MappedMatch<'a>>, Error>;
+
+  /// Yields all non-overlapping matches after normalizing the text and pattern, or an empty vector if the regex fails
+  fn pattern_matches_normalized(&'a self, pattern: &str, form: NormalizationForm, case_insensitive: bool) -> Vec<MappedMatch<'a>> {
+    self.pattern_matches_normalized_result(pattern, form, case_insensitive).unwrap_or_default()
+  }
+
+  /// Yields the first match after normalizing the text and pattern, if any
+  fn pattern_first_match_normalized(&'a self, pattern: &str, form: NormalizationForm, case_insensitive: bool) -> Option<MappedMatch<'a>> {
+    self.pattern_matches_normalized(pattern, form, case_insensitive).into_iter().next()
+  }
+
+  /// Yields the capture groups of all non-overlapping matches after normalizing the text and pattern,
+  /// with offsets in the original text, or an error if the pattern is not a valid regular expression
+  fn pattern_captures_normalized_result(&'a self, pattern: &str, form: NormalizationForm, case_insensitive: bool) -> Result<Vec<MappedCaptures<'a>>, Error> where Self: AsRef<str> {
+    let re = build_normalized_regex(pattern, form, case_insensitive)?;
+    let normalized = MappedText::normalized(self.as_ref(), form);
+    Ok(re.captures_all(normalized.as_str()).map(|captures| normalized.to_original_captures(&captures)).collect())
+  }
+
+  /// Yields the capture groups of all non-overlapping matches after normalizing the text and pattern,
+  /// or an empty vector if the regex fails
+  fn pattern_captures_normalized(&'a self, pattern: &str, form: NormalizationForm, case_insensitive: bool) -> Vec<MappedCaptures<'a>> where Self: AsRef<str> {
+    self.pattern_captures_normalized_result(pattern, form, case_insensitive).unwrap_or_default()
+  }
+
+  /// Matches after normalizing the text and pattern with a boolean case_insensitive flag. Returns false if the regex fails
+  fn pattern_match_normalized(&'a self, pattern: &str, form: NormalizationForm, case_insensitive: bool) -> bool {
+    self.pattern_first_match_normalized(pattern, form, case_insensitive).is_some()
+  }
+
+  /// Matches in case-insensitive mode after normalizing the text and pattern
+  fn pattern_match_normalized_ci(&'a self, pattern: &str, form: NormalizationForm) -> bool {
+    self.pattern_match_normalized(pattern, form, true)
+  }
+
+  /// Matches in case-sensitive mode after normalizing the text and pattern
+  fn pattern_match_normalized_cs(&'a self, pattern: &str, form: NormalizationForm) -> bool {
+    self.pattern_match_normalized(pattern, form, false)
+  }
+
+  /// Matches a whole word after normalizing the text and word with a boolean case_insensitive flag
+  fn match_word_normalized(&'a self, word: &str, form: NormalizationForm, case_insensitive: bool) -> bool {
+    self.pattern_match_normalized(&build_whole_word_pattern(word), form, case_insensitive)
+  }
+
+  /// Counts matches after normalizing the text and pattern
+  fn count_pattern_normalized(&'a self, pattern: &str, form: NormalizationForm, case_insensitive: bool) -> usize {
+    self.pattern_matches_normalized(pattern, form, case_insensitive).len()
+  }
+}
+
+impl<'a> MatchNormalized<'a> for str {
+  fn pattern_matches_normalized_result(&'a self, pattern: &str, form: NormalizationForm, case_insensitive: bool) -> Result<Vec<MappedMatch<'a>>, Error> {
+    let re = build_normalized_regex(pattern, form, case_insensitive)?;
+    let normalized = MappedText::normalized(self, form);
+    Ok(re.find_all(normalized.as_str()).map(|matched_item| normalized.to_original_match(matched_item.start(), matched_item.end())).collect())
+  }
+}
+
+/// Replacement methods that match after normalizing the text and pattern to the same Unicode normalization form.
+/// Only the original characters covered by each match are replaced and the rest of the text keeps its form.
+/// Capture group references in the replacement refer to the normalized match
+pub trait ReplaceNormalized where Self:Sized {
+  /// Replaces all matches after normalizing the text and pattern,
+  /// or returns an error if the pattern is not a valid regular expression
+  fn pattern_replace_normalized_result(&self, pattern: &str, replacement: &str, form: NormalizationForm, case_insensitive: bool) -> Result<Self, Error>;
+
+  /// Replaces all matches after normalizing the text and pattern with a boolean case_insensitive flag.
+  /// If the regex fails, nothing will be replaced
+  fn pattern_replace_normalized(&self, pattern: &str, replacement: &str, form: NormalizationForm, case_insensitive: bool) -> Self where Self:Clone {
+    self.pattern_replace_normalized_result(pattern, replacement, form, case_insensitive).unwrap_or_else(|_| self.clone())
+  }
+
+  /// Replaces all matches in case-insensitive mode after normalizing the text and pattern
+  fn pattern_replace_normalized_ci(&self, pattern: &str, replacement: &str, form: NormalizationForm) -> Self where Self:Clone {
+    self.pattern_replace_normalized(pattern, replacement, form, true)
+  }
+
+  /// Replaces all matches in case-sensitive mode after normalizing the text and pattern
+  fn pattern_replace_normalized_cs(&self, pattern: &str, replacement: &str, form: NormalizationForm) -> Self where Self:Clone {
+    self.pattern_replace_normalized(pattern, replacement, form, false)
+  }
+}
+
+fn replace_normalized(text: &str, re: &Regex, replacement: &str, form: NormalizationForm) -> String {
+  let normalized = MappedText::normalized(text, form);
+  let mut output = String::with_capacity(text.len());
+  let mut position = 0;
+  for captures in re.captures_all(normalized.as_str()) {
+    let Some(matched_item) = captures.get(0) else {
+      continue;
+    };
+    let (start, end) = normalized.to_original_range(matched_item.start(), matched_item.end());
+    // matches within a character already replaced by a previous match are skipped
+    if start < position {
+      continue;
+    }
+    output.push_str(&text[position..start]);
+    captures.expand(replacement, &mut output);
+    position = end;
+  }
+  output.push_str(&text[position..]);
+  output
+}
+
+impl ReplaceNormalized for String {
+  fn pattern_replace_normalized_result(&self, pattern: &str, replacement: &str, form: NormalizationForm, case_insensitive: bool) -> Result<String, Error> {
+    let re = build_normalized_regex(pattern, form, case_insensitive)?;
+    Ok(replace_normalized(self, &re, replacement, form))
+  }
+}
+
+impl ReplaceNormalized for Vec<String> {
+  fn pattern_replace_normalized_result(&self, pattern: &str, replacement: &str, form: NormalizationForm, case_insensitive: bool) -> Result<Vec<String>, Error> {
+    let re = build_normalized_regex(pattern, form, case_insensitive)?;
+    Ok(self.iter().map(|text| replace_normalized(text, &re, replacement, form)).collect())
+  }
+}
@@ -1,5 +1,11 @@
-use regex::{Captures, Match, Regex};
+use std::collections::VecDeque;
+use crate::engine::{Captures, Match, Regex, RegexExt};
 
+use crate::boundary::{find_bounded_words, WordBoundary};
+use crate::enums::WordBounds;
+use crate::positions::{match_positions, MatchPosition};
+#[cfg(feature = "segmentation")]
+use crate::segmentation::find_segmented_words;
 use crate::utils::{build_regex, build_whole_word_pattern};
 
 /// Set of methods to capture groups or match objects derived from Regex::captures.
@@ -28,19 +34,31 @@ pub trait PatternCapture<'a> {
   /// As this uses re.find it will be fast than the matching last_match method
   fn pattern_first_match(&'a self, pattern: &str, case_insensitive: bool) -> Option<Match<'a>>;
 
- /// Yields an option with last match object if available with a boolean case_insensitive flag
- fn pattern_last_match(&'a self, pattern: &str, case_insensitive: bool) -> Option<Match> {
-   let matched_segments = self.pattern_matches_vec(pattern, case_insensitive);
-   matched_segments.last().map(|m| *m)
+ /// Yields a vector of up to num_matches outer Match objects from the end of the haystack in their original order
+ /// with a boolean case_insensitive flag. Inner groups are ignored
+ fn pattern_last_matches(&'a self, pattern: &str, case_insensitive: bool, num_matches: usize) -> Vec<Match<'a>> {
+   let matched_segments = self.pattern_matches_outer(pattern, case_insensitive);
+   let num_skipped = matched_segments.len().saturating_sub(num_matches);
+   matched_segments.into_iter().skip(num_skipped).collect()
  }
 
- /// returns an option with a pair of match objects
+ /// Yields an option with last outer match object if available with a boolean case_insensitive flag
+ fn pattern_last_match(&'a self, pattern: &str, case_insensitive: bool) -> Option<Match<'a>> {
+   self.pattern_last_matches(pattern, case_insensitive, 1).pop()
+ }
+
+ /// Yields an option with the last outer match object ending at or before the given byte offset
+ /// with a boolean case_insensitive flag
+ fn pattern_last_match_before(&'a self, pattern: &str, case_insensitive: bool, offset: usize) -> Option<Match<'a>> {
+   self.pattern_matches_outer(pattern, case_insensitive).into_iter().take_while(|m| m.end() <= offset).last()
+ }
+
+ /// returns an option with a pair of outer match objects
  /// If there is only one match the match objects will have the same indices
- fn pattern_first_last_matches(&'a self, pattern: &str, case_insensitive: bool) -> Option<(Match, Match)> {
-   let matched_segments = self.pattern_matches_vec(pattern, case_insensitive);
-   if let Some(first) = matched_segments.get(0) {
-     if let Some(last) = matched_segments.last() {
-       return Some((*first, *last));
+ fn pattern_first_last_matches(&'a self, pattern: &str, case_insensitive: bool) -> Option<(Match<'a>, Match<'a>)> {
+   if let Some(first) = self.pattern_first_match(pattern, case_insensitive) {
+     if let Some(last) = self.pattern_last_match(pattern, case_insensitive) {
+       return Some((first, last));
      }
    }
    None
@@ -69,21 +87,64 @@ pub trait PatternCapture<'a> {
  /// Yields an option with an unsigned integer for the index of the start of the last match
  /// with a boolean case_insensitive flag
  fn pattern_last_start_index(&'a self, pattern: &str, case_insensitive: bool) -> Option<usize> {
-   if let Some(first) = self.pattern_first_match(pattern, case_insensitive) {
-     Some(first.start())
-   } else {
-     None
-   }
+   self.pattern_last_match(pattern, case_insensitive).map(|last| last.start())
  }
 
- // Yields an option with an unsigned integer for the index of the end of the last match
+ /// Yields an option with an unsigned integer for the index of the end of the last match
  /// with a boolean case_insensitive flag
  fn pattern_last_index(&'a self, pattern: &str, case_insensitive: bool) -> Option<usize> {
-   if let Some(first) = self.pattern_first_match(pattern, case_insensitive) {
-     Some(first.end())
-   } else {
-     None
-   }
+   self.pattern_last_match(pattern, case_insensitive).map(|last| last.end())
+ }
+
+ /// Yields a vector of outer matches with start and end positions as byte, char and UTF-16 offsets
+ /// and zero-based line and column numbers, with a boolean case_insensitive flag.
+ /// Match positions are calculated in a single pass over the haystack
+ fn pattern_match_positions(&'a self, pattern: &str, case_insensitive: bool) -> Vec<MatchPosition<'a>> where Self: AsRef<str> {
+   let matched_items = self.pattern_matches_outer(pattern, case_insensitive);
+   match_positions(self.as_ref(), &matched_items)
+ }
+
+ /// Yields an option with the positions of the first match in all coordinate systems.
+ /// Only scans the haystack up to the end of the first match
+ fn pattern_first_position(&'a self, pattern: &str, case_insensitive: bool) -> Option<MatchPosition<'a>> where Self: AsRef<str> {
+   self.pattern_first_match(pattern, case_insensitive).and_then(|first| match_positions(self.as_ref(), &[first]).pop())
+ }
+
+ /// Yields an option with the positions of the last match in all coordinate systems
+ fn pattern_last_position(&'a self, pattern: &str, case_insensitive: bool) -> Option<MatchPosition<'a>> where Self: AsRef<str> {
+   self.pattern_last_match(pattern, case_insensitive).and_then(|last| match_positions(self.as_ref(), &[last]).pop())
+ }
+
+ /// Yields an option with the char index of the start of the first match, rather than its byte offset
+ fn pattern_first_char_index(&'a self, pattern: &str, case_insensitive: bool) -> Option<usize> where Self: AsRef<str> {
+   self.pattern_first_position(pattern, case_insensitive).map(|first| first.start.char_index)
+ }
+
+ /// Yields an option with the UTF-16 offset of the start of the first match for use with Javascript strings
+ fn pattern_first_utf16_index(&'a self, pattern: &str, case_insensitive: bool) -> Option<usize> where Self: AsRef<str> {
+   self.pattern_first_position(pattern, case_insensitive).map(|first| first.start.utf16)
+ }
+
+ /// Yields a vector of overlapping Match objects with two modes, outer will whole groups only, otherwise unique matched groups and subgroups
+ /// The search restarts one character after the start of each match, so "aa" matches three times in "aaaa"
+ fn pattern_matches_overlapping_as_vec(&'a self, pattern: &str, case_insensitive: bool, outer: bool) -> Vec<Match<'a>> where Self: AsRef<str> {
+   let (matched_items, _rgx) = find_overlapping_matches_within_haystack(self.as_ref(), pattern, case_insensitive, outer);
+   matched_items
+ }
+
+ /// Yields a vector of overlapping outer matches for the whole pattern with a boolean case_insensitive flag
+ fn pattern_matches_overlapping(&'a self, pattern: &str, case_insensitive: bool) -> Vec<Match<'a>> where Self: AsRef<str> {
+   self.pattern_matches_overlapping_as_vec(pattern, case_insensitive, true)
+ }
+
+ /// Yields a vector of unique matched groups and subgroups from overlapping matches with a boolean case_insensitive flag
+ fn pattern_matches_overlapping_vec(&'a self, pattern: &str, case_insensitive: bool) -> Vec<Match<'a>> where Self: AsRef<str> {
+   self.pattern_matches_overlapping_as_vec(pattern, case_insensitive, false)
+ }
+
+ /// Counts the number of overlapping matches with a boolean case_insensitive flag
+ fn count_pattern_overlapping(&'a self, pattern: &str, case_insensitive: bool) -> usize where Self: AsRef<str> {
+   self.pattern_matches_overlapping(pattern, case_insensitive).len()
  }
 
  // Counts the number of matches with a boolean case_insensitive flag
@@ -96,39 +157,95 @@ pub trait PatternCapture<'a> {
    let pattern = build_whole_word_pattern(word);
    self.pattern_matches_vec(&pattern, case_insensitive).len()
  }
+
+ /// Yields a vector of word matches with bounds and a custom word boundary definition, excluding the
+ /// characters before and after each word, with a boolean case_insensitive flag.
+ /// Returns an empty vector if the word pattern is not a valid regular expression
+ fn word_matches_boundary(&'a self, word: &str, bounds: WordBounds, boundary: &WordBoundary, case_insensitive: bool) -> Vec<Match<'a>> where Self: AsRef<str> {
+   find_bounded_words(self.as_ref(), word, bounds, boundary, case_insensitive).unwrap_or_default()
+ }
+
+ // Counts whole words with a custom word boundary definition and a boolean case_insensitive flag
+ fn count_word_boundary(&'a self, word: &str, boundary: &WordBoundary, case_insensitive: bool) -> usize where Self: AsRef<str> {
+   self.word_matches_boundary(word, WordBounds::Both, boundary, case_insensitive).len()
+ }
+
+ /// Yields the parts of Unicode word tokens (UAX #29) matching a word pattern with bounds within each token,
+ /// with a boolean case_insensitive flag. Returns an empty vector if the word pattern is not a valid regular expression
+ #[cfg(feature = "segmentation")]
+ fn word_matches_segmented(&'a self, word: &str, bounds: WordBounds, case_insensitive: bool) -> Vec<Match<'a>> where Self: AsRef<str> {
+   find_segmented_words(self.as_ref(), word, bounds, case_insensitive).unwrap_or_default()
+ }
+
+ // Counts whole Unicode word tokens matching a word pattern with a boolean case_insensitive flag
+ #[cfg(feature = "segmentation")]
+ fn count_word_segmented(&'a self, word: &str, case_insensitive: bool) -> usize where Self: AsRef<str> {
+   self.word_matches_segmented(word, WordBounds::Both, case_insensitive).len()
+ }
 }
 
 
 /// This function is the basis for both pattern_matches_vec() and pattern_matches_outer()
-/// and will be used with string-patterns-extras to replicate look-ahead and look-behind behaviour
+/// Look-ahead and look-behind behaviour is emulated separately by find_captures_with_lookaround()
 /// It returns a flattened vector of Match objects
 /// The outer options limits the matches to the whole matched sequence and excludes inner groups
 pub fn find_matches_within_haystack<'a>(haystack: &'a str, pattern: &str, case_insensitive: bool, outer: bool) -> (Vec<Match<'a>>, Option<Regex>) {
+  let mut matched_items: Vec<Match<'a>> = Vec::new();
+  if let Ok(re) = build_regex(pattern, case_insensitive) {
+    matched_items = find_matches_with_regex(haystack, &re, outer);
+    (matched_items, Some(re))
+  } else {
+    (matched_items, None)
+  }
+}
+
+/// Core of find_matches_within_haystack() with a compiled regular expression,
+/// so that it may be reused with arrays or vectors of strings
+pub(crate) fn find_matches_with_regex<'a>(haystack: &'a str, re: &Regex, outer: bool) -> Vec<Match<'a>> {
+  let mut matched_items: Vec<Match<'a>> = Vec::new();
+  let mut item_keys: Vec<(&str, usize, usize)> = Vec::new();
+  for inner_captures in re.captures_all(haystack) {
+    collect_captured_matches(&inner_captures, outer, &mut matched_items, &mut item_keys);
+  }
+  matched_items
+}
+
+/// Adds the outer match or all unique matched groups and subgroups of a captures object to the matched items
+fn collect_captured_matches<'a>(captures: &Captures<'a>, outer: bool, matched_items: &mut Vec<Match<'a>>, item_keys: &mut Vec<(&'a str, usize, usize)>) {
+  for matched_item in captures.iter().flatten() {
+    let item_key = (matched_item.as_str(), matched_item.start(), matched_item.end());
+    let is_matched = outer || !item_keys.contains(&item_key);
+    if is_matched {
+      matched_items.push(matched_item);
+      if !outer {
+        item_keys.push(item_key);
+      }
+    }
+    // if only capturing the first group of outer matches, break the inner loop here and move onto the next outer group
+    if outer {
+      break;
+    }
+  }
+}
+
+/// Overlapping counterpart of find_matches_within_haystack()
+/// After each match the search resumes one character after the start of the match rather than at its end,
+/// e.g. "aa" matches three times in "aaaa". The regex context is preserved, so anchors and word boundaries
+/// are evaluated against the whole haystack.
+/// The outer options limits the matches to the whole matched sequence and excludes inner groups
+pub fn find_overlapping_matches_within_haystack<'a>(haystack: &'a str, pattern: &str, case_insensitive: bool, outer: bool) -> (Vec<Match<'a>>, Option<Regex>) {
   let mut matched_items: Vec<Match<'a>> = Vec::new();
   if let Ok(re) = build_regex(pattern, case_insensitive) {
     let mut item_keys: Vec<(&str, usize, usize)> = Vec::new();
-    for inner_captures in re.captures_iter(haystack) {
-      for capture_opt in inner_captures.iter() {
-        if let Some(matched_item) = capture_opt {
-          let item_str = matched_item.as_str();
-          
-          let item_key = (item_str, matched_item.start(), matched_item.end());
-          let is_matched = if outer { 
-            true
-          } else {
-            item_keys.contains(&item_key) == false
-          };
-          if is_matched {
-            matched_items.push(matched_item.to_owned());
-            if !outer {
-              item_keys.push(item_key);
-            }
-          }
-          // if only capturing the first group of outer matches, break the inner loop here and move onto the next outer group
-          if outer {
-            break;
-          }
-        }
+    let mut start = 0;
+    while start <= haystack.len() {
+      if let Some(inner_captures) = re.captures_from(haystack, start) {
+        collect_captured_matches(&inner_captures, outer, &mut matched_items, &mut item_keys);
+        let match_start = inner_captures.get(0).map(|m| m.start()).unwrap_or(start);
+        let next_char_len = haystack[match_start..].chars().next().map(|c| c.len_utf8()).unwrap_or(1);
+        start = match_start + next_char_len;
+      } else {
+        break;
       }
     }
     (matched_items, Some(re))
@@ -137,13 +254,37 @@ pub fn find_matches_within_haystack<'a>(haystack: &'a str, pattern: &str, case_i
   }
 }
 
+/// Reverse-search counterpart of find_matches_within_haystack() and the basis for the pattern_last_* methods
+/// It returns up to num_matches outer matches from the end of the haystack in their original order
+/// Only outer matches are evaluated and no more than num_matches are held while scanning the haystack
+/// If max_end is set, matches ending after this byte offset are ignored
+pub fn find_last_matches_within_haystack<'a>(haystack: &'a str, pattern: &str, case_insensitive: bool, num_matches: usize, max_end: Option<usize>) -> (Vec<Match<'a>>, Option<Regex>) {
+  if let Ok(re) = build_regex(pattern, case_insensitive) {
+    let mut matched_items: VecDeque<Match<'a>> = VecDeque::new();
+    if num_matches > 0 {
+      for matched_item in re.find_all(haystack) {
+        if max_end.is_some_and(|end| matched_item.end() > end) {
+          break;
+        }
+        if matched_items.len() == num_matches {
+          matched_items.pop_front();
+        }
+        matched_items.push_back(matched_item);
+      }
+    }
+    (matched_items.into(), Some(re))
+  } else {
+    (Vec::new(), None)
+  }
+}
+
 /// Implementation for &str/String
 impl<'a> PatternCapture<'a> for str {
 
   /// Yields an option with Regex::Captures as returned from re.captures, Accepts a boolean case_insensitive flag
   fn pattern_captures(&self, pattern: &str, case_insensitive: bool) -> Option<Captures> {
     if let Ok(re) = build_regex(pattern, case_insensitive) {
-      re.captures(self)
+      re.captures_first(self)
     } else {
       None
     }
@@ -160,12 +301,110 @@ impl<'a> PatternCapture<'a> for str {
   /// Implemented here to shortcut the larger find_matches_within_haystack function
   fn pattern_first_match(&'a self, pattern: &str, case_insensitive: bool) -> Option<Match<'a>> {
     if let Ok(re) = build_regex(pattern, case_insensitive) {
-      re.find(self)
+      re.find_first(self)
     } else {
       None
     }
   }
 
+  /// Yields up to num_matches outer matches from the end of the haystack
+  /// Implemented here to avoid collecting all inner groups via find_matches_within_haystack
+  fn pattern_last_matches(&'a self, pattern: &str, case_insensitive: bool, num_matches: usize) -> Vec<Match<'a>> {
+    let (matched_items, _rgx) = find_last_matches_within_haystack(self, pattern, case_insensitive, num_matches, None);
+    matched_items
+  }
+
+  /// Yields the last outer match ending at or before the given byte offset
+  fn pattern_last_match_before(&'a self, pattern: &str, case_insensitive: bool, offset: usize) -> Option<Match<'a>> {
+    let (mut matched_items, _rgx) = find_last_matches_within_haystack(self, pattern, case_insensitive, 1, Some(offset));
+    matched_items.pop()
+  }
+
+  /// Yields the first and last outer matches, compiling the regular expression only once
+  fn pattern_first_last_matches(&'a self, pattern: &str, case_insensitive: bool) -> Option<(Match<'a>, Match<'a>)> {
+    if let Ok(re) = build_regex(pattern, case_insensitive) {
+      let mut matched_items = re.find_all(self);
+      if let Some(first) = matched_items.next() {
+        let last = matched_items.last().unwrap_or(first);
+        return Some((first, last));
+      }
+    }
+    None
+  }
+
 }
 
+/// Capture methods for arrays or vectors of strings that return results for each matching item with its index
+/// The regular expression is compiled only once. If the regex fails, the results will be empty.
+/// Only the indexed captures, matches and per-item counts need to be implemented
+pub trait PatternCaptures<'a> {
 
+  /// Yields a vector of tuples with the item index and Regex::Captures for the first match in each matching item
+  /// with a boolean case_insensitive flag
+  fn pattern_captures_indexed(&'a self, pattern: &str, case_insensitive: bool) -> Vec<(usize, Captures<'a>)>;
+
+  /// Yields a vector of tuples with the item index and its Match objects for each matching item with two modes,
+  /// outer will whole groups only, otherwise unique matched groups and subgroups
+  fn pattern_matches_as_vec_indexed(&'a self, pattern: &str, case_insensitive: bool, outer: bool) -> Vec<(usize, Vec<Match<'a>>)>;
+
+  /// Yields a vector of tuples with the item index and unique matched groups and subgroups for each matching item
+  fn pattern_matches_vec_indexed(&'a self, pattern: &str, case_insensitive: bool) -> Vec<(usize, Vec<Match<'a>>)> {
+    self.pattern_matches_as_vec_indexed(pattern, case_insensitive, false)
+  }
+
+  /// Yields a vector of tuples with the item index and outer matches for the whole pattern for each matching item
+  fn pattern_matches_outer_indexed(&'a self, pattern: &str, case_insensitive: bool) -> Vec<(usize, Vec<Match<'a>>)> {
+    self.pattern_matches_as_vec_indexed(pattern, case_insensitive, true)
+  }
+
+  /// Counts the number of matches in each item, in the same order as the source items
+  /// As with count_pattern on a single string, matched subgroups are counted too
+  fn count_pattern_per_item(&'a self, pattern: &str, case_insensitive: bool) -> Vec<usize>;
+
+  /// Counts the number of matches in all items with a boolean case_insensitive flag
+  fn count_pattern(&'a self, pattern: &str, case_insensitive: bool) -> usize {
+    self.count_pattern_per_item(pattern, case_insensitive).into_iter().sum()
+  }
+
+  /// Counts the number of whole word matches in each item with a boolean case_insensitive flag
+  fn count_word_per_item(&'a self, word: &str, case_insensitive: bool) -> Vec<usize> {
+    let pattern = build_whole_word_pattern(word);
+    self.count_pattern_per_item(&pattern, case_insensitive)
+  }
+
+  /// Counts the number of whole word matches in all items with a boolean case_insensitive flag
+  fn count_word(&'a self, word: &str, case_insensitive: bool) -> usize {
+    self.count_word_per_item(word, case_insensitive).into_iter().sum()
+  }
+}
+
+/// Implementation for arrays or vectors of owned strings or string slices
+impl<'a, T> PatternCaptures<'a> for [T] where T: AsRef<str> + 'a {
+
+  fn pattern_captures_indexed(&'a self, pattern: &str, case_insensitive: bool) -> Vec<(usize, Captures<'a>)> {
+    if let Ok(re) = build_regex(pattern, case_insensitive) {
+      self.iter().enumerate().filter_map(|(index, item)| re.captures_first(item.as_ref()).map(|captures| (index, captures))).collect()
+    } else {
+      Vec::new()
+    }
+  }
+
+  fn pattern_matches_as_vec_indexed(&'a self, pattern: &str, case_insensitive: bool, outer: bool) -> Vec<(usize, Vec<Match<'a>>)> {
+    if let Ok(re) = build_regex(pattern, case_insensitive) {
+      self.iter().enumerate()
+        .map(|(index, item)| (index, find_matches_with_regex(item.as_ref(), &re, outer)))
+        .filter(|(_index, matched_items)| !matched_items.is_empty())
+        .collect()
+    } else {
+      Vec::new()
+    }
+  }
+
+  fn count_pattern_per_item(&'a self, pattern: &str, case_insensitive: bool) -> Vec<usize> {
+    if let Ok(re) = build_regex(pattern, case_insensitive) {
+      self.iter().map(|item| find_matches_with_regex(item.as_ref(), &re, false).len()).collect()
+    } else {
+      vec![0; self.len()]
+    }
+  }
+}
@@ -1,4 +1,10 @@
-use crate::{build_regex, utils::build_whole_word_pattern};
+use crate::boundary::WordBoundary;
+use crate::dialects::dialect_to_regex;
+use crate::engine::escape_literal;
+use crate::enums::PatternDialect;
+#[cfg(feature = "segmentation")]
+use crate::segmentation::filter_segmented_words;
+use crate::{prefilter::build_prefiltered_regex, utils::{build_literal_word_pattern, build_whole_word_pattern, build_word_list_pattern}, WordBounds};
 
 /// Trait with methods to filter arrays or vectors of strings by regular expression patterns
 /// Only pattern_filter() method needs to be implemented.
@@ -33,13 +39,91 @@ pub trait PatternFilter<'a, T> where T:Sized {
   fn pattern_filter_word_cs(&'a self, pattern: &str) -> Vec<T> {
     self.pattern_filter_word(pattern, false)
   }
+
+  /// Filters strings containing any of the literal words as whole words with case-insensitive flag.
+  /// The words are escaped and compiled into a single trie-factored pattern
+  fn pattern_filter_any_words(&'a self, words: &[&str], case_insensitive: bool) -> Vec<T> {
+    let pattern = build_word_list_pattern(words, WordBounds::Both);
+    self.pattern_filter(&pattern, case_insensitive)
+  }
+
+  /// Filters strings containing any of the literal words as whole words in case-insensitive mode
+  fn pattern_filter_any_words_ci(&'a self, words: &[&str]) -> Vec<T> {
+    self.pattern_filter_any_words(words, true)
+  }
+
+  /// Filters strings containing any of the literal words as whole words in case-sensitive mode
+  fn pattern_filter_any_words_cs(&'a self, words: &[&str]) -> Vec<T> {
+    self.pattern_filter_any_words(words, false)
+  }
+
+  /// Filters strings containing the literal text with case-insensitive flag, escaping all regex meta characters
+  fn pattern_filter_literal(&'a self, text: &str, case_insensitive: bool) -> Vec<T> {
+    self.pattern_filter(&escape_literal(text), case_insensitive)
+  }
+
+  /// Filters strings containing the literal word as a whole word with case-insensitive flag.
+  /// Word boundaries only apply at word-character edges, so c++ matches in "c++ code"
+  fn pattern_filter_word_literal(&'a self, word: &str, case_insensitive: bool) -> Vec<T> {
+    self.pattern_filter(&build_literal_word_pattern(word, WordBounds::Both), case_insensitive)
+  }
+
+  /// Filters strings by whole word regex patterns with a custom word boundary definition and case-insensitive flag
+  fn pattern_filter_word_boundary(&'a self, pattern: &str, boundary: &WordBoundary, case_insensitive: bool) -> Vec<T> {
+    self.pattern_filter(&WordBounds::Both.to_pattern_with(pattern, boundary), case_insensitive)
+  }
+
+  /// Filters strings with a Unicode word token (UAX #29) matching the word pattern with bounds within
+  /// each token and a case-insensitive flag
+  #[cfg(feature = "segmentation")]
+  fn pattern_filter_word_bounds_segmented(&'a self, pattern: &str, bounds: WordBounds, case_insensitive: bool) -> Vec<T> where Self: AsRef<[T]>, T: AsRef<str> + Clone {
+    filter_segmented_words(self.as_ref(), pattern, bounds, case_insensitive)
+  }
+
+  /// Filters strings with a whole Unicode word token matching the word pattern with case-insensitive flag
+  #[cfg(feature = "segmentation")]
+  fn pattern_filter_word_segmented(&'a self, pattern: &str, case_insensitive: bool) -> Vec<T> where Self: AsRef<[T]>, T: AsRef<str> + Clone {
+    self.pattern_filter_word_bounds_segmented(pattern, WordBounds::Both, case_insensitive)
+  }
+
+  /// Filters strings with a whole Unicode word token matching the word pattern in case-insensitive mode
+  #[cfg(feature = "segmentation")]
+  fn pattern_filter_word_segmented_ci(&'a self, pattern: &str) -> Vec<T> where Self: AsRef<[T]>, T: AsRef<str> + Clone {
+    self.pattern_filter_word_segmented(pattern, true)
+  }
+
+  /// Filters strings with a whole Unicode word token matching the word pattern in case-sensitive mode
+  #[cfg(feature = "segmentation")]
+  fn pattern_filter_word_segmented_cs(&'a self, pattern: &str) -> Vec<T> where Self: AsRef<[T]>, T: AsRef<str> + Clone {
+    self.pattern_filter_word_segmented(pattern, false)
+  }
+
+  /// Filters strings wholly matching a pattern in the given dialect with case-insensitive flag
+  fn pattern_filter_dialect(&'a self, pattern: &str, dialect: PatternDialect, case_insensitive: bool) -> Vec<T> {
+    self.pattern_filter(&dialect_to_regex(pattern, dialect), case_insensitive)
+  }
+
+  /// Filters strings matching a shell glob such as *.log or report_??.csv with case-insensitive flag
+  fn pattern_filter_glob(&'a self, glob: &str, case_insensitive: bool) -> Vec<T> {
+    self.pattern_filter_dialect(glob, PatternDialect::Glob, case_insensitive)
+  }
+
+  /// Filters strings matching a case-sensitive SQL LIKE pattern with \ as the escape character
+  fn pattern_filter_like(&'a self, pattern: &str) -> Vec<T> {
+    self.pattern_filter_dialect(pattern, PatternDialect::Like(Some('\\')), false)
+  }
+
+  /// Filters strings matching a case-insensitive SQL ILIKE pattern with \ as the escape character
+  fn pattern_filter_ilike(&'a self, pattern: &str) -> Vec<T> {
+    self.pattern_filter_dialect(pattern, PatternDialect::Like(Some('\\')), true)
+  }
 }
 
 impl<'a> PatternFilter<'a, String> for [String] {
   /// Filter an array of strs by the pattern
   fn pattern_filter(&'a self, pattern: &str, case_insensitive: bool) -> Vec<String> {
-    if let Ok(re) = build_regex(pattern, case_insensitive) {
-      self.into_iter().filter(|s| re.is_match(s)).map(|s| s.to_owned()).collect::<Vec<String>>()
+    if let Ok(re) = build_prefiltered_regex(pattern, case_insensitive) {
+      self.iter().filter(|s| re.matches_text(s)).map(|s| s.to_owned()).collect::<Vec<String>>()
     } else {
       self.to_owned()
     }
@@ -49,8 +133,8 @@ impl<'a> PatternFilter<'a, String> for [String] {
 impl<'a> PatternFilter<'a, &'a str> for [&str] {
   /// Filter an array of strs by the pattern
   fn pattern_filter(&'a self, pattern: &str, case_insensitive: bool) -> Vec<&str> {
-    if let Ok(re) = build_regex(pattern, case_insensitive) {
-      self.into_iter().filter(|s| re.is_match(s)).map(|s| s.to_owned()).collect::<Vec<&str>>()
+    if let Ok(re) = build_prefiltered_regex(pattern, case_insensitive) {
+      self.iter().filter(|s| re.matches_text(s)).map(|s| s.to_owned()).collect::<Vec<&str>>()
     } else {
       self.into_iter().map(|s| s.to_owned()).collect::<Vec<&str>>()
     }
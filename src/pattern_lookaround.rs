@@ -0,0 +1,155 @@
+use crate::engine::{Captures, Error, Match, Regex, RegexExt};
+use crate::enums::LookAround;
+use crate::utils::build_regex;
+
+/// Compiled look-ahead or look-behind condition.
+/// Look-ahead patterns are searched from the end of the match within the whole haystack and must match
+/// immediately at that offset, so anchors and word boundaries see the text before the match.
+/// Look-behind patterns are anchored at the end of the haystack before the match, which is searched again
+/// for every candidate match, so look-behind conditions cost O(n²) in the worst case on long texts
+/// with many candidate matches. Word boundaries at the end of a look-behind pattern are evaluated without
+/// the text of the match.
+struct LookAroundRegex {
+  re: Regex,
+  ahead: bool,
+  positive: bool,
+}
+
+impl LookAroundRegex {
+  fn new(condition: &LookAround, case_insensitive: bool) -> Result<LookAroundRegex, Error> {
+    let flag = if case_insensitive { "(?i)" } else { "" };
+    let pattern = condition.pattern();
+    let anchored_pattern = if condition.is_ahead() {
+      [flag, "(?:", pattern, ")"].concat()
+    } else {
+      [flag, "(?:", pattern, r#")\z"#].concat()
+    };
+    // the case-insensitive flag is already set above, as the anchored pattern may start with a non-capturing group
+    let re = build_regex(&anchored_pattern, false)?;
+    Ok(LookAroundRegex { re, ahead: condition.is_ahead(), positive: condition.is_positive() })
+  }
+
+  fn is_satisfied(&self, haystack: &str, matched_item: &Match) -> bool {
+    let is_matched = if self.ahead {
+      // the leftmost match from the end offset starts there if any match does
+      self.re.find_from(haystack, matched_item.end()).is_some_and(|next_match| next_match.start() == matched_item.end())
+    } else {
+      self.re.matches_text(&haystack[..matched_item.start()])
+    };
+    is_matched == self.positive
+  }
+}
+
+/// Compiles the main pattern and look-around conditions, returning the first error if any regex fails
+fn build_lookaround_regexes(pattern: &str, conditions: &[LookAround], case_insensitive: bool) -> Result<(Regex, Vec<LookAroundRegex>), Error> {
+  let re = build_regex(pattern, case_insensitive)?;
+  let mut condition_regexes: Vec<LookAroundRegex> = Vec::with_capacity(conditions.len());
+  for condition in conditions {
+    condition_regexes.push(LookAroundRegex::new(condition, case_insensitive)?);
+  }
+  Ok((re, condition_regexes))
+}
+
+/// Emulates look-ahead and look-behind assertions by filtering the matches of the main pattern.
+/// Matches are kept only if all conditions are satisfied immediately after or before the whole match.
+/// Unlike real look-around assertions, conditions do not cause the main pattern to backtrack to shorter matches,
+/// e.g. \d+ not followed by px will skip 100px entirely rather than match 10.
+/// Returns a vector of Regex::Captures for each accepted match or an error if any regular expression fails
+pub fn find_captures_with_lookaround<'a>(haystack: &'a str, pattern: &str, conditions: &[LookAround], case_insensitive: bool) -> Result<Vec<Captures<'a>>, Error> {
+  let (re, condition_regexes) = build_lookaround_regexes(pattern, conditions, case_insensitive)?;
+  let captures = re.captures_all(haystack).filter(|inner_captures| {
+    if let Some(matched_item) = inner_captures.get(0) {
+      condition_regexes.iter().all(|condition| condition.is_satisfied(haystack, &matched_item))
+    } else {
+      false
+    }
+  }).collect();
+  Ok(captures)
+}
+
+/// Set of match, capture, replace and split methods with look-ahead and look-behind conditions
+/// expressed as an array of LookAround enums, e.g. &[LookAround::FollowedBy(r#"\s*kg\b"#), LookAround::NotPrecededBy(r#"-"#)]
+/// Only the captures, replace and split methods without default implementations need to be implemented
+pub trait PatternLookAround<'a> {
+
+  /// Yields a result with a vector of Regex::Captures for all matches satisfying the conditions
+  /// or an error if any regex fails
+  fn pattern_captures_around_result(&'a self, pattern: &str, conditions: &[LookAround], case_insensitive: bool) -> Result<Vec<Captures<'a>>, Error>;
+
+  /// Yields a vector of Regex::Captures for all matches satisfying the conditions. The vector will be empty if any regex fails
+  fn pattern_captures_around(&'a self, pattern: &str, conditions: &[LookAround], case_insensitive: bool) -> Vec<Captures<'a>> {
+    self.pattern_captures_around_result(pattern, conditions, case_insensitive).unwrap_or_default()
+  }
+
+  /// Yields a vector of outer Match objects satisfying the conditions
+  fn pattern_matches_around(&'a self, pattern: &str, conditions: &[LookAround], case_insensitive: bool) -> Vec<Match<'a>> {
+    self.pattern_captures_around(pattern, conditions, case_insensitive).iter().filter_map(|captures| captures.get(0)).collect()
+  }
+
+  /// Apply a regular expression match with look-around conditions and a boolean case_insensitive flag
+  /// If any regex doesn't compile it will return an error
+  fn pattern_match_around_result(&'a self, pattern: &str, conditions: &[LookAround], case_insensitive: bool) -> Result<bool, Error> {
+    self.pattern_captures_around_result(pattern, conditions, case_insensitive).map(|captures| !captures.is_empty())
+  }
+
+  /// Apply a regular expression match with look-around conditions and a boolean case_insensitive flag
+  /// NB: If any regex doesn't compile it will return false
+  fn pattern_match_around(&'a self, pattern: &str, conditions: &[LookAround], case_insensitive: bool) -> bool {
+    self.pattern_match_around_result(pattern, conditions, case_insensitive).unwrap_or(false)
+  }
+
+  /// Replace all matches satisfying the conditions. The replacement may reference captured groups, e.g. $1 or ${name}.
+  /// If any regex doesn't compile it will return an error
+  fn pattern_replace_around_result(&'a self, pattern: &str, conditions: &[LookAround], replacement: &str, case_insensitive: bool) -> Result<String, Error>;
+
+  /// Replace all matches satisfying the conditions. If any regex fails, nothing will be replaced
+  fn pattern_replace_around(&'a self, pattern: &str, conditions: &[LookAround], replacement: &str, case_insensitive: bool) -> String;
+
+  /// Splits a string on matches satisfying the conditions or returns an error if any regex fails
+  fn pattern_split_around_result(&'a self, pattern: &str, conditions: &[LookAround], case_insensitive: bool) -> Result<Vec<String>, Error>;
+
+  /// Splits a string on matches satisfying the conditions. Returns an empty vector if any regex fails
+  fn pattern_split_around(&'a self, pattern: &str, conditions: &[LookAround], case_insensitive: bool) -> Vec<String> {
+    self.pattern_split_around_result(pattern, conditions, case_insensitive).unwrap_or_default()
+  }
+}
+
+/// Implemented for &str and available to String too
+impl<'a> PatternLookAround<'a> for str {
+
+  fn pattern_captures_around_result(&'a self, pattern: &str, conditions: &[LookAround], case_insensitive: bool) -> Result<Vec<Captures<'a>>, Error> {
+    find_captures_with_lookaround(self, pattern, conditions, case_insensitive)
+  }
+
+  /// Replacement groups are expanded as with Regex::replace_all
+  fn pattern_replace_around_result(&'a self, pattern: &str, conditions: &[LookAround], replacement: &str, case_insensitive: bool) -> Result<String, Error> {
+    let captures = find_captures_with_lookaround(self, pattern, conditions, case_insensitive)?;
+    let mut output = String::with_capacity(self.len());
+    let mut last_end = 0;
+    for inner_captures in captures {
+      if let Some(matched_item) = inner_captures.get(0) {
+        output.push_str(&self[last_end..matched_item.start()]);
+        inner_captures.expand(replacement, &mut output);
+        last_end = matched_item.end();
+      }
+    }
+    output.push_str(&self[last_end..]);
+    Ok(output)
+  }
+
+  fn pattern_replace_around(&'a self, pattern: &str, conditions: &[LookAround], replacement: &str, case_insensitive: bool) -> String {
+    self.pattern_replace_around_result(pattern, conditions, replacement, case_insensitive).unwrap_or(self.to_owned())
+  }
+
+  fn pattern_split_around_result(&'a self, pattern: &str, conditions: &[LookAround], case_insensitive: bool) -> Result<Vec<String>, Error> {
+    let matched_items = find_captures_with_lookaround(self, pattern, conditions, case_insensitive)?;
+    let mut parts: Vec<String> = Vec::with_capacity(matched_items.len() + 1);
+    let mut last_end = 0;
+    for matched_item in matched_items.iter().filter_map(|captures| captures.get(0)) {
+      parts.push(self[last_end..matched_item.start()].to_string());
+      last_end = matched_item.end();
+    }
+    parts.push(self[last_end..].to_string());
+    Ok(parts)
+  }
+}
@@ -1,5 +1,8 @@
-use regex::Error;
-use crate::utils::build_regex;
+use crate::engine::{escape_literal, Error, RegexExt};
+use crate::prefilter::{build_prefiltered_regex, PrefilteredRegex};
+use crate::dialects::dialect_to_regex;
+use crate::enums::PatternDialect;
+use crate::utils::{build_regex, build_flex_space_pattern};
 
 /// Core regular expression match methods
 pub trait PatternMatch {
@@ -28,6 +31,39 @@ pub trait PatternMatch {
     self.pattern_match(pattern, false)
   }
 
+  /// Matches the text literally with a boolean case_insensitive flag, escaping all regex meta characters,
+  /// e.g. c++ or $5.00 in user-supplied search terms
+  fn pattern_match_literal(&self, text: &str, case_insensitive: bool) -> bool {
+    self.pattern_match(&escape_literal(text), case_insensitive)
+  }
+
+  /// Matches with a boolean case_insensitive flag, where any run of whitespace in the pattern matches
+  /// any run of whitespace in the text, including line breaks and no-break spaces
+  fn pattern_match_flex_space(&self, pattern: &str, case_insensitive: bool) -> bool {
+    self.pattern_match(&build_flex_space_pattern(pattern), case_insensitive)
+  }
+
+  /// Matches a pattern in the given dialect with a boolean case_insensitive flag.
+  /// Glob, LIKE and wildcard patterns must match the whole string
+  fn pattern_match_dialect(&self, pattern: &str, dialect: PatternDialect, case_insensitive: bool) -> bool {
+    self.pattern_match(&dialect_to_regex(pattern, dialect), case_insensitive)
+  }
+
+  /// Matches a shell glob such as *.log or report_??.csv with a boolean case_insensitive flag
+  fn pattern_match_glob(&self, glob: &str, case_insensitive: bool) -> bool {
+    self.pattern_match_dialect(glob, PatternDialect::Glob, case_insensitive)
+  }
+
+  /// Matches a case-sensitive SQL LIKE pattern such as %smith% with \ as the escape character
+  fn pattern_match_like(&self, pattern: &str) -> bool {
+    self.pattern_match_dialect(pattern, PatternDialect::Like(Some('\\')), false)
+  }
+
+  /// Matches a case-insensitive SQL ILIKE pattern with \ as the escape character
+  fn pattern_match_ilike(&self, pattern: &str) -> bool {
+    self.pattern_match_dialect(pattern, PatternDialect::Like(Some('\\')), true)
+  }
+
 }
 
 /// Implement regular expression match and replace methods for str and owned String
@@ -41,18 +77,28 @@ impl PatternMatch for str {
   /// Only the pattern_match_result needs to be implemented
   fn pattern_match_result(&self, pattern: &str, case_insensitive: bool) -> Result<bool, Error> {
     match build_regex(pattern, case_insensitive) {
-      Ok(re)  => Ok(re.is_match(self)),
+      Ok(re)  => re.is_match_result(self),
       Err(error) => Err(error)
     }
   }
 }
 
+/// Returns true if any of the string slices match or the first runtime error, if the regex backend may fail at runtime
+fn any_match_result<'a>(re: &PrefilteredRegex, segments: impl Iterator<Item = &'a str>) -> Result<bool, Error> {
+  for segment in segments {
+    if re.is_match_result(segment)? {
+      return Ok(true);
+    }
+  }
+  Ok(false)
+}
+
 /// Boolean methods to match a pattern within an array of strings
 impl PatternMatch for [&str] {
   /// The regex is only compiled when validating an array of strings
   fn pattern_match_result(&self, pattern: &str, case_insensitive: bool) -> Result<bool, Error> {
-    match build_regex(pattern, case_insensitive) {
-      Ok(re) => Ok(self.into_iter().any(|segment| re.is_match(*segment))),
+    match build_prefiltered_regex(pattern, case_insensitive) {
+      Ok(re) => any_match_result(&re, self.iter().copied()),
       Err(error) => Err(error)
     }
   }
@@ -62,8 +108,8 @@ impl PatternMatch for [&str] {
 impl PatternMatch for [String] {
   /// The regex is only compiled when validating an array of strings
   fn pattern_match_result(&self, pattern: &str, case_insensitive: bool) -> Result<bool, Error> {
-    match build_regex(pattern, case_insensitive) {
-      Ok(re) => Ok(self.into_iter().any(|segment| re.is_match(segment))),
+    match build_prefiltered_regex(pattern, case_insensitive) {
+      Ok(re) => any_match_result(&re, self.iter().map(|segment| segment.as_str())),
       Err(error) => Err(error)
     }
   }
@@ -140,8 +186,8 @@ impl PatternMatches for [&str] {
   /// Returns an Ok result with a vector of boolean matches for an array or vector of strings with a case-insensitive flag
   /// and an error only if the regex fails to compile.
   fn pattern_matched_pairs_result(&self, pattern: &str, case_insensitive: bool) -> Result<Vec<(bool, &str)>, Error> {
-    match build_regex(pattern, case_insensitive) {
-      Ok(re) => Ok(self.into_iter().map(|segment| (re.is_match(*segment), *segment)).collect::<Vec<(bool, &str)>>()),
+    match build_prefiltered_regex(pattern, case_insensitive) {
+      Ok(re) => self.iter().map(|segment| re.is_match_result(segment).map(|is_matched| (is_matched, *segment))).collect::<Result<Vec<(bool, &str)>, Error>>(),
       Err(error) => Err(error)
     }
   }
@@ -161,8 +207,8 @@ impl PatternMatches for [String] {
   /// Returns an Ok result with a vector of boolean matches for an array or vector of strings with a case-insensitive flag
   /// and an error only if the regex fails to compile.
   fn pattern_matched_pairs_result(&self, pattern: &str, case_insensitive: bool) -> Result<Vec<(bool, &str)>, Error> {
-    match build_regex(pattern, case_insensitive) {
-      Ok(re) => Ok(self.into_iter().map(|segment| (re.is_match(segment), segment.as_str())).collect::<Vec<(bool, &str)>>()),
+    match build_prefiltered_regex(pattern, case_insensitive) {
+      Ok(re) => self.iter().map(|segment| re.is_match_result(segment).map(|is_matched| (is_matched, segment.as_str()))).collect::<Result<Vec<(bool, &str)>, Error>>(),
       Err(error) => Err(error)
     }
   }
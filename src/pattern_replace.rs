@@ -1,5 +1,5 @@
-use regex::Error;
-use crate::utils::build_regex;
+use crate::engine::{escape_literal, Error, RegexExt};
+use crate::utils::{build_regex, escape_replacement};
 use std::borrow::ToOwned;
 
 /// Core regular expression replacement methods 
@@ -49,6 +49,18 @@ pub trait PatternReplace where Self:Sized {
     self.pattern_replace_first(pattern, replacement, false)
   }
 
+  /// Replace all literal occurrences of the text with a boolean case_insensitive flag.
+  /// Regex meta characters in the text are escaped and $ in the replacement is inserted as is
+  fn pattern_replace_literal(&self, text: &str, replacement: &str, case_insensitive: bool) -> Self where Self:Sized {
+    self.pattern_replace(&escape_literal(text), &escape_replacement(replacement), case_insensitive)
+  }
+
+  /// Replace the first literal occurrence of the text with a boolean case_insensitive flag.
+  /// Regex meta characters in the text are escaped and $ in the replacement is inserted as is
+  fn pattern_replace_first_literal(&self, text: &str, replacement: &str, case_insensitive: bool) -> Self where Self:Sized {
+    self.pattern_replace_first(&escape_literal(text), &escape_replacement(replacement), case_insensitive)
+  }
+
 }
 
 /// Core regex replacement methods for Strings
@@ -57,7 +69,7 @@ impl PatternReplace for String {
   /// Regex-enabled replace method that will return an OK String result if successful and an error if the regex fails
   fn pattern_replace_result(&self, pattern: &str, replacement: &str, case_insensitive: bool) -> Result<String, Error> {
     match build_regex(pattern, case_insensitive) {
-      Ok(re) => Ok(re.replace_all(self, replacement).to_string()),
+      Ok(re) => re.replace_all_result(self, replacement),
       Err(error) => Err(error)
     }
   }
@@ -65,7 +77,7 @@ impl PatternReplace for String {
   /// Regex-enabled replace method that will return an OK String result if successful and an error if the regex fails
   fn pattern_replace_first_result(&self, pattern: &str, replacement: &str, case_insensitive: bool) -> Result<String, Error> {
     match build_regex(pattern, case_insensitive) {
-      Ok(re) => Ok(re.replace(self, replacement).to_string()),
+      Ok(re) => re.replace_first_result(self, replacement),
       Err(error) => Err(error)
     }
   }
@@ -90,10 +102,9 @@ impl PatternReplace for Vec<String> {
   fn pattern_replace_result(&self, pattern: &str, replacement: &str, case_insensitive: bool) -> Result<Vec<String>, Error> {
     match build_regex(pattern, case_insensitive) {
       Ok(re) => {
-        let replacements = self.into_iter()
-            .map(|segment| re.replace_all(segment, replacement).to_string())
-            .collect::<Vec<String>>();
-        Ok(replacements)
+        self.iter()
+            .map(|segment| re.replace_all_result(segment, replacement))
+            .collect::<Result<Vec<String>, Error>>()
       },
       Err(error) => Err(error)
     }
@@ -105,10 +116,9 @@ impl PatternReplace for Vec<String> {
   fn pattern_replace_first_result(&self, pattern: &str, replacement: &str, case_insensitive: bool) -> Result<Vec<String>, Error> {
     match build_regex(pattern, case_insensitive) {
       Ok(re) => {
-        let replacements = self.into_iter()
-            .map(|segment| re.replace(segment, replacement).to_string())
-            .collect::<Vec<String>>();
-        Ok(replacements)
+        self.iter()
+            .map(|segment| re.replace_first_result(segment, replacement))
+            .collect::<Result<Vec<String>, Error>>()
       },
       Err(error) => Err(error)
     }
@@ -1,4 +1,4 @@
-use regex::Error;
+use crate::engine::{escape_literal, Error, RegexExt};
 use crate::utils::build_regex;
 
 /// Provides methods to split a &str/string on a regular expression
@@ -53,6 +53,18 @@ pub trait PatternSplit {
     self.pattern_split_pair(pattern, false)
   }
 
+  /// Splits a string on a literal separator with boolean case_insensitive flag, escaping all regex meta characters.
+  /// Returns a vector of strings
+  fn pattern_split_literal(&self, separator: &str, case_insensitive: bool) -> Vec<String> {
+    self.pattern_split(&escape_literal(separator), case_insensitive)
+  }
+
+  /// Splits a string on the first literal separator with boolean case_insensitive flag, escaping all regex meta characters.
+  /// Returns a tuple with head and tail. The tail will be en empty string if not matched
+  fn pattern_split_pair_literal(&self, separator: &str, case_insensitive: bool) -> (String, String) {
+    self.pattern_split_pair(&escape_literal(separator), case_insensitive)
+  }
+
 }
 
 /// Implemented for &str and available to String too
@@ -61,7 +73,7 @@ impl PatternSplit for str {
   /// Split a string on a regular expression into a result with a vector of strings
   fn pattern_split_result(&self, pattern: &str, case_sensitive: bool) -> Result<Vec<String>, Error> {
     match build_regex(pattern, case_sensitive) {
-      Ok(regex) => Ok(regex.split(self).into_iter().map(|s| s.to_string()).collect::<Vec<String>>()),
+      Ok(regex) => regex.split_result(self).map(|parts| parts.into_iter().map(|s| s.to_string()).collect::<Vec<String>>()),
       Err(error) => Err(error),
     }
   }
@@ -70,7 +82,7 @@ impl PatternSplit for str {
   fn pattern_split_pair_result(&self, pattern: &str, case_sensitive: bool) -> Result<(String, String), Error> {
     match build_regex(pattern, case_sensitive) {
       Ok(regex) => {
-        let parts = regex.splitn(self, 2).collect::<Vec<&str>>();
+        let parts = regex.splitn_result(self, 2)?;
         let head = parts.get(0).unwrap_or(&"").to_owned().to_string();
         let tail = parts.get(1).unwrap_or(&"").to_owned().to_string();
         Ok((head, tail))
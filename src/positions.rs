@@ -0,0 +1,150 @@
+use crate::engine::Match;
+
+/// Location of a byte offset within a string expressed in all supported coordinate systems
+/// - byte: byte offset as used by regex::Match and string slices
+/// - char_index: offset in Unicode scalar values (chars)
+/// - utf16: offset in UTF-16 code units as used by Javascript strings
+/// - line and column: zero-based line number and char offset from the start of the line.
+///
+/// Lines are separated by \n only, so a preceding \r counts as the last column of its line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TextPosition {
+  pub byte: usize,
+  pub char_index: usize,
+  pub utf16: usize,
+  pub line: usize,
+  pub column: usize,
+}
+
+/// Match with start and end positions in all coordinate systems and the matched string slice
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MatchPosition<'a> {
+  pub start: TextPosition,
+  pub end: TextPosition,
+  text: &'a str,
+}
+
+impl<'a> MatchPosition<'a> {
+  /// Returns the matched string slice
+  pub fn as_str(&self) -> &'a str {
+    self.text
+  }
+
+  /// Start and end offsets in chars
+  pub fn char_range(&self) -> (usize, usize) {
+    (self.start.char_index, self.end.char_index)
+  }
+
+  /// Start and end offsets in UTF-16 code units
+  pub fn utf16_range(&self) -> (usize, usize) {
+    (self.start.utf16, self.end.utf16)
+  }
+}
+
+/// Converts offsets within a string from one coordinate system to another.
+/// The mapper keeps track of its last position and only scans forward, so a long document is only scanned once
+/// when offsets are requested in ascending order, e.g. for a vector of matches.
+/// Looking up an offset before the last position restarts the scan from the beginning.
+pub struct PositionMapper<'a> {
+  text: &'a str,
+  current: TextPosition,
+}
+
+impl<'a> PositionMapper<'a> {
+
+  /// Creates a mapper for the text, starting at its first character
+  pub fn new(text: &'a str) -> Self {
+    PositionMapper { text, current: TextPosition::default() }
+  }
+
+  /// Move forward by one char, returning false at the end of the text
+  fn advance(&mut self) -> bool {
+    if let Some(next_char) = self.text[self.current.byte..].chars().next() {
+      self.current.byte += next_char.len_utf8();
+      self.current.char_index += 1;
+      self.current.utf16 += next_char.len_utf16();
+      if next_char == '\n' {
+        self.current.line += 1;
+        self.current.column = 0;
+      } else {
+        self.current.column += 1;
+      }
+      true
+    } else {
+      false
+    }
+  }
+
+  /// Scan forward while the position is before the target, restarting from the beginning if the current position is already past it
+  fn seek<F, G>(&mut self, is_before: F, is_past: G) -> Option<TextPosition> where F: Fn(&TextPosition) -> bool, G: Fn(&TextPosition) -> bool {
+    if is_past(&self.current) {
+      self.current = TextPosition::default();
+    }
+    while is_before(&self.current) {
+      if !self.advance() {
+        return None;
+      }
+    }
+    Some(self.current)
+  }
+
+  /// Position of a byte offset. Offsets within a multibyte character resolve to the start of the character
+  /// and offsets beyond the end of the text resolve to the end position
+  pub fn position_at_byte(&mut self, byte: usize) -> TextPosition {
+    let mut target = byte.min(self.text.len());
+    while !self.text.is_char_boundary(target) {
+      target -= 1;
+    }
+    self.seek(|pos| pos.byte < target, |pos| pos.byte > target).unwrap_or(self.current)
+  }
+
+  /// Position of a char index or None if it is beyond the end of the text
+  pub fn position_at_char(&mut self, char_index: usize) -> Option<TextPosition> {
+    self.seek(|pos| pos.char_index < char_index, |pos| pos.char_index > char_index)
+  }
+
+  /// Position of a UTF-16 offset or None if it is beyond the end of the text or splits a surrogate pair
+  pub fn position_at_utf16(&mut self, utf16_index: usize) -> Option<TextPosition> {
+    self.seek(|pos| pos.utf16 < utf16_index, |pos| pos.utf16 > utf16_index).filter(|pos| pos.utf16 == utf16_index)
+  }
+
+  /// Position of a zero-based line and column or None if the column is beyond the end of the line
+  pub fn position_at_line_column(&mut self, line: usize, column: usize) -> Option<TextPosition> {
+    let position = self.seek(|pos| (pos.line, pos.column) < (line, column), |pos| (pos.line, pos.column) > (line, column));
+    position.filter(|pos| pos.line == line && pos.column == column)
+  }
+
+  /// Match positions for a sequence of match objects found within the same text
+  pub fn match_position(&mut self, matched_item: &Match<'a>) -> MatchPosition<'a> {
+    let start = self.position_at_byte(matched_item.start());
+    let end = self.position_at_byte(matched_item.end());
+    MatchPosition { start, end, text: matched_item.as_str() }
+  }
+}
+
+/// Converts a vector of match objects from the same haystack to match positions in one pass if the matches are in ascending order
+pub fn match_positions<'a>(haystack: &'a str, matched_items: &[Match<'a>]) -> Vec<MatchPosition<'a>> {
+  let mut mapper = PositionMapper::new(haystack);
+  matched_items.iter().map(|matched_item| mapper.match_position(matched_item)).collect()
+}
+
+/// Position of a byte offset within a text in all coordinate systems
+pub fn byte_index_to_position(text: &str, byte_index: usize) -> TextPosition {
+  PositionMapper::new(text).position_at_byte(byte_index)
+}
+
+/// Converts a char index to a byte index, returning None if it is beyond the end of the text
+pub fn char_index_to_byte_index(text: &str, char_index: usize) -> Option<usize> {
+  PositionMapper::new(text).position_at_char(char_index).map(|pos| pos.byte)
+}
+
+/// Converts a UTF-16 code unit offset to a byte index, returning None if it is beyond the end of the text
+/// or falls within a surrogate pair
+pub fn utf16_index_to_byte_index(text: &str, utf16_index: usize) -> Option<usize> {
+  PositionMapper::new(text).position_at_utf16(utf16_index).map(|pos| pos.byte)
+}
+
+/// Converts a zero-based line number and char column to a byte index, returning None if it lies outside the text
+pub fn line_column_to_byte_index(text: &str, line: usize, column: usize) -> Option<usize> {
+  PositionMapper::new(text).position_at_line_column(line, column).map(|pos| pos.byte)
+}
@@ -0,0 +1,257 @@
+// Literal prefilters for collection methods that run one regex over many strings.
+// With the prefilter feature, the literals every match must contain are extracted from the compiled pattern
+// via regex-syntax, e.g. ERROR and timeout in ERROR.*timeout, and strings lacking any of them are rejected
+// with memchr or Aho-Corasick before the regex is run. A prefilter only ever rejects strings that cannot match,
+// so results are identical with or without it. Patterns regex-syntax cannot parse, such as fancy-regex
+// look-around, and patterns without required literals are not prefiltered. Literals containing case-insensitive
+// letters are skipped, as searching them with ASCII case folding was slower than a plain loop, so case-insensitive
+// patterns are only prefiltered by digits, punctuation and case-sensitive parts.
+use crate::engine::{Error, Regex, RegexExt};
+use crate::utils::build_regex;
+
+/// Builds a regex as with build_regex together with a prefilter for its required literals
+pub(crate) fn build_prefiltered_regex(pattern: &str, case_insensitive: bool) -> Result<PrefilteredRegex, Error> {
+  build_regex(pattern, case_insensitive).map(PrefilteredRegex::new)
+}
+
+/// Compiled regex with an optional literal prefilter
+pub(crate) struct PrefilteredRegex {
+  re: Regex,
+  prefilter: Option<Prefilter>,
+}
+
+impl PrefilteredRegex {
+  pub(crate) fn new(re: Regex) -> PrefilteredRegex {
+    let prefilter = Prefilter::new(re.as_str());
+    PrefilteredRegex { re, prefilter }
+  }
+
+  /// False if the text lacks a required literal, otherwise true
+  fn may_match(&self, text: &str) -> bool {
+    self.prefilter.as_ref().map_or(true, |prefilter| prefilter.may_match(text))
+  }
+
+  /// Returns true if the regex matches the text or an error if the regex fails at runtime
+  pub(crate) fn is_match_result(&self, text: &str) -> Result<bool, Error> {
+    if self.may_match(text) {
+      self.re.is_match_result(text)
+    } else {
+      Ok(false)
+    }
+  }
+
+  /// Returns true if the regex matches the text, false if not or if the regex fails at runtime
+  pub(crate) fn matches_text(&self, text: &str) -> bool {
+    self.may_match(text) && self.re.matches_text(text)
+  }
+}
+
+#[cfg(not(feature = "prefilter"))]
+struct Prefilter;
+
+#[cfg(not(feature = "prefilter"))]
+impl Prefilter {
+  fn new(_pattern: &str) -> Option<Prefilter> {
+    None
+  }
+
+  fn may_match(&self, _text: &str) -> bool {
+    true
+  }
+}
+
+#[cfg(feature = "prefilter")]
+pub(crate) use literal_prefilter::Prefilter;
+
+#[cfg(feature = "prefilter")]
+mod literal_prefilter {
+  use aho_corasick::AhoCorasick;
+  use memchr::memmem::Finder;
+  use regex_syntax::hir::{Class, Hir, HirKind};
+
+  /// Literal that must occur in any match, optionally matched with ASCII case folding
+  #[derive(Debug, Clone)]
+  struct RequiredLiteral {
+    text: String,
+    case_insensitive: bool,
+  }
+
+  /// A match must contain at least one of the literals in a clause
+  type Clause = Vec<RequiredLiteral>;
+
+  /// Required literals of a subexpression
+  struct LiteralInfo {
+    /// the literal the subexpression always matches exactly, if any
+    exact: Option<RequiredLiteral>,
+    /// clauses that must all be satisfied
+    clauses: Vec<Clause>,
+  }
+
+  impl LiteralInfo {
+    fn none() -> LiteralInfo {
+      LiteralInfo { exact: None, clauses: vec![] }
+    }
+
+    fn exact(literal: RequiredLiteral) -> LiteralInfo {
+      LiteralInfo { exact: Some(literal), clauses: vec![] }
+    }
+
+    /// The longest literal required by the subexpression on its own
+    fn best_literal(&self) -> Option<RequiredLiteral> {
+      if let Some(literal) = &self.exact {
+        return Some(literal.clone());
+      }
+      self.clauses.iter().filter(|clause| clause.len() == 1).map(|clause| clause[0].clone()).max_by_key(|literal| literal.text.len())
+    }
+
+    /// All clauses, including the exact literal
+    fn into_clauses(self) -> Vec<Clause> {
+      let mut clauses = self.clauses;
+      if let Some(literal) = self.exact {
+        clauses.push(vec![literal]);
+      }
+      clauses
+    }
+  }
+
+  /// Returns the lower-case ASCII letter if the class matches exactly its upper and lower case forms, as with (?i)a.
+  /// Classes with non-ASCII case variants, e.g. k and the Kelvin sign K, are not treated as literals
+  fn case_folded_char(class: &Class) -> Option<char> {
+    let Class::Unicode(class) = class else {
+      return None;
+    };
+    let ranges = class.ranges();
+    if ranges.len() != 2 || ranges.iter().any(|range| range.start() != range.end()) {
+      return None;
+    }
+    let (first, second) = (ranges[0].start(), ranges[1].start());
+    if first.is_ascii_uppercase() && first.to_ascii_lowercase() == second {
+      Some(second)
+    } else {
+      None
+    }
+  }
+
+  /// Appends a literal to the current run, switching the run to case-insensitive if needed
+  fn extend_run(run: &mut Option<RequiredLiteral>, literal: &RequiredLiteral) {
+    match run {
+      Some(current) => {
+        current.text.push_str(&literal.text);
+        current.case_insensitive |= literal.case_insensitive;
+      },
+      None => *run = Some(literal.clone()),
+    }
+  }
+
+  fn extract(hir: &Hir) -> LiteralInfo {
+    match hir.kind() {
+      HirKind::Empty | HirKind::Look(_) => LiteralInfo::exact(RequiredLiteral { text: String::new(), case_insensitive: false }),
+      HirKind::Literal(literal) => match std::str::from_utf8(&literal.0) {
+        Ok(text) => LiteralInfo::exact(RequiredLiteral { text: text.to_owned(), case_insensitive: false }),
+        Err(_) => LiteralInfo::none(),
+      },
+      HirKind::Class(class) => match case_folded_char(class) {
+        Some(ch) => LiteralInfo::exact(RequiredLiteral { text: ch.to_string(), case_insensitive: true }),
+        None => LiteralInfo::none(),
+      },
+      HirKind::Capture(capture) => extract(&capture.sub),
+      HirKind::Repetition(repetition) => {
+        if repetition.min == 0 {
+          return LiteralInfo::none();
+        }
+        let info = extract(&repetition.sub);
+        if repetition.min == 1 && repetition.max == Some(1) {
+          return info;
+        }
+        LiteralInfo { exact: None, clauses: info.into_clauses() }
+      },
+      HirKind::Concat(children) => {
+        let mut clauses: Vec<Clause> = vec![];
+        let mut run: Option<RequiredLiteral> = None;
+        let mut is_exact = true;
+        for child in children {
+          let info = extract(child);
+          if let Some(literal) = &info.exact {
+            extend_run(&mut run, literal);
+            continue;
+          }
+          is_exact = false;
+          if let Some(literal) = run.take() {
+            clauses.push(vec![literal]);
+          }
+          clauses.extend(info.into_clauses());
+        }
+        if is_exact {
+          return LiteralInfo { exact: run, clauses };
+        }
+        if let Some(literal) = run {
+          clauses.push(vec![literal]);
+        }
+        LiteralInfo { exact: None, clauses }
+      },
+      HirKind::Alternation(branches) => {
+        let literals: Option<Clause> = branches.iter().map(|branch| extract(branch).best_literal()).collect();
+        match literals {
+          Some(clause) => LiteralInfo { exact: None, clauses: vec![clause] },
+          None => LiteralInfo::none(),
+        }
+      },
+    }
+  }
+
+  /// Searcher for one clause
+  enum ClauseSearcher {
+    Single(Box<Finder<'static>>),
+    Multiple(AhoCorasick),
+  }
+
+  impl ClauseSearcher {
+    /// Returns None for clauses with case-insensitive literals, as a case-folding search is slower
+    /// than running the regex on most texts
+    fn new(clause: &Clause) -> Option<ClauseSearcher> {
+      if clause.iter().any(|literal| literal.case_insensitive) {
+        return None;
+      }
+      if let [literal] = clause.as_slice() {
+        return Some(ClauseSearcher::Single(Box::new(Finder::new(literal.text.as_bytes()).into_owned())));
+      }
+      let patterns: Vec<&str> = clause.iter().map(|literal| literal.text.as_str()).collect();
+      AhoCorasick::builder().build(patterns).ok().map(ClauseSearcher::Multiple)
+    }
+
+    fn is_found(&self, text: &str) -> bool {
+      match self {
+        ClauseSearcher::Single(finder) => finder.find(text.as_bytes()).is_some(),
+        ClauseSearcher::Multiple(searcher) => searcher.is_match(text),
+      }
+    }
+  }
+
+  /// Rejects strings lacking any literal required by a pattern
+  pub(crate) struct Prefilter {
+    searchers: Vec<ClauseSearcher>,
+  }
+
+  impl Prefilter {
+    /// Builds a prefilter from a pattern or returns None if it cannot be parsed or has no required literals
+    pub(crate) fn new(pattern: &str) -> Option<Prefilter> {
+      let hir = regex_syntax::parse(pattern).ok()?;
+      let mut clauses = extract(&hir).into_clauses();
+      // empty literals, e.g. from zero-width assertions, are always found
+      clauses.retain(|clause| clause.iter().all(|literal| !literal.text.is_empty()));
+      // longer literals are rarer, so they are searched first
+      clauses.sort_by_key(|clause| std::cmp::Reverse(clause.iter().map(|literal| literal.text.len()).min().unwrap_or(0)));
+      let searchers: Vec<ClauseSearcher> = clauses.iter().filter_map(ClauseSearcher::new).collect();
+      if searchers.is_empty() {
+        None
+      } else {
+        Some(Prefilter { searchers })
+      }
+    }
+
+    /// False if the text lacks a required literal
+    pub(crate) fn may_match(&self, text: &str) -> bool {
+      self.searchers.iter().all(|searcher| searcher.is_found(text))
+    }
+  }
+}
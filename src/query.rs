@@ -0,0 +1,464 @@
+use std::fmt;
+use crate::engine::{escape_literal, Regex, RegexExt};
+use crate::utils::{build_regex, is_word_char};
+
+// Boolean search queries such as: cat AND (dog OR mouse) -fish "exact phrase"
+// Queries are parsed once into an expression tree of whole-word or phrase terms,
+// each compiled to a single regular expression, and may then be evaluated against many strings.
+
+/// Errors raised while parsing a search query. Positions are byte offsets within the query string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryError {
+  /// The query has no terms
+  EmptyQuery,
+  /// A quoted phrase has no closing quote
+  UnterminatedPhrase(usize),
+  /// A quoted phrase has no words
+  EmptyPhrase(usize),
+  /// An opening parenthesis has no closing parenthesis
+  UnclosedParenthesis(usize),
+  /// An operator or closing parenthesis where a term was expected
+  UnexpectedToken(usize, String),
+  /// AND, OR, NOT or - is not followed by a term
+  MissingOperand(usize),
+  /// A term could not be compiled to a regular expression, e.g. if it exceeds the size limit
+  InvalidPattern(String),
+}
+
+impl fmt::Display for QueryError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      QueryError::EmptyQuery => write!(f, "the query has no search terms"),
+      QueryError::UnterminatedPhrase(position) => write!(f, "unterminated phrase starting at {}", position),
+      QueryError::EmptyPhrase(position) => write!(f, "empty phrase at {}", position),
+      QueryError::UnclosedParenthesis(position) => write!(f, "unclosed parenthesis at {}", position),
+      QueryError::UnexpectedToken(position, token) => write!(f, "unexpected {} at {}", token, position),
+      QueryError::MissingOperand(position) => write!(f, "missing search term after operator at {}", position),
+      QueryError::InvalidPattern(message) => write!(f, "invalid search term: {}", message),
+    }
+  }
+}
+
+impl std::error::Error for QueryError {}
+
+/// Search term matching a whole word or a phrase of whole words separated by whitespace.
+/// The wildcards * and ? match zero or more and exactly one word character, e.g. colo*r matches color and colour
+#[derive(Debug, Clone)]
+pub struct QueryTerm {
+  words: Vec<String>,
+  is_phrase: bool,
+  pattern: String,
+  re: Regex,
+}
+
+impl QueryTerm {
+  fn new(words: Vec<String>, is_phrase: bool, case_insensitive: bool) -> Result<QueryTerm, QueryError> {
+    let pattern = build_phrase_pattern(&words);
+    let re = build_regex(&pattern, case_insensitive).map_err(|error| QueryError::InvalidPattern(error.to_string()))?;
+    Ok(QueryTerm { words, is_phrase, pattern, re })
+  }
+
+  /// The words of the term as entered, including any wildcards
+  pub fn words(&self) -> &[String] {
+    &self.words
+  }
+
+  /// True if the term was entered as a quoted phrase
+  pub fn is_phrase(&self) -> bool {
+    self.is_phrase
+  }
+
+  /// True if any word contains the * or ? wildcards
+  pub fn has_wildcards(&self) -> bool {
+    self.words.iter().any(|word| word.contains(['*', '?']))
+  }
+
+  /// The regular expression pattern the term is compiled to, without the case-insensitive flag
+  pub fn pattern(&self) -> &str {
+    &self.pattern
+  }
+
+  /// The compiled regular expression
+  pub fn regex(&self) -> &Regex {
+    &self.re
+  }
+
+  /// True if the term matches the text
+  pub fn is_match(&self, text: &str) -> bool {
+    self.re.matches_text(text)
+  }
+}
+
+/// Expression tree of a parsed search query
+#[derive(Debug, Clone)]
+pub enum QueryNode {
+  Term(QueryTerm),
+  And(Vec<QueryNode>),
+  Or(Vec<QueryNode>),
+  Not(Box<QueryNode>),
+}
+
+impl QueryNode {
+  /// Evaluate the expression against a text, matching each term with its compiled regex
+  pub fn is_match(&self, text: &str) -> bool {
+    self.evaluate(&|term: &QueryTerm| term.is_match(text))
+  }
+
+  /// Evaluate the expression with a custom term matcher, e.g. to look up terms in an index
+  pub fn evaluate<F>(&self, is_term_matched: &F) -> bool where F: Fn(&QueryTerm) -> bool {
+    match self {
+      QueryNode::Term(term) => is_term_matched(term),
+      QueryNode::And(nodes) => nodes.iter().all(|node| node.evaluate(is_term_matched)),
+      QueryNode::Or(nodes) => nodes.iter().any(|node| node.evaluate(is_term_matched)),
+      QueryNode::Not(node) => !node.evaluate(is_term_matched),
+    }
+  }
+
+  /// Collect terms that must or may match for the expression to succeed, i.e. terms not negated an odd number of times
+  fn collect_terms<'a>(&'a self, negated: bool, positive: bool, terms: &mut Vec<&'a QueryTerm>) {
+    match self {
+      QueryNode::Term(term) => if negated != positive {
+        terms.push(term);
+      },
+      QueryNode::And(nodes) | QueryNode::Or(nodes) => for node in nodes {
+        node.collect_terms(negated, positive, terms);
+      },
+      QueryNode::Not(node) => node.collect_terms(!negated, positive, terms),
+    }
+  }
+}
+
+/// Parsed and compiled boolean search query.
+/// Syntax:
+/// - words are matched as whole words, with * and ? as wildcards for word characters, e.g. colo*r
+/// - "quoted phrases" match consecutive words separated by any whitespace
+/// - AND, OR and NOT operators in upper case, with AND implied between adjacent terms
+/// - -word excludes a word or phrase, like NOT
+/// - parentheses group sub-expressions. NOT binds tighter than AND, and AND tighter than OR
+#[derive(Debug, Clone)]
+pub struct SearchQuery {
+  root: QueryNode,
+  case_insensitive: bool,
+}
+
+impl SearchQuery {
+  /// Parse and compile a query with a case_insensitive flag or return a QueryError
+  pub fn new(query: &str, case_insensitive: bool) -> Result<SearchQuery, QueryError> {
+    let tokens = tokenize_query(query)?;
+    if tokens.is_empty() {
+      return Err(QueryError::EmptyQuery);
+    }
+    let mut parser = QueryParser { tokens, index: 0, end: query.len(), case_insensitive };
+    let root = parser.parse_or()?;
+    if let Some((position, token)) = parser.tokens.get(parser.index) {
+      return Err(QueryError::UnexpectedToken(*position, token.label()));
+    }
+    Ok(SearchQuery { root, case_insensitive })
+  }
+
+  /// The root of the expression tree
+  pub fn root(&self) -> &QueryNode {
+    &self.root
+  }
+
+  pub fn is_case_insensitive(&self) -> bool {
+    self.case_insensitive
+  }
+
+  /// True if the text satisfies the query
+  pub fn is_match(&self, text: &str) -> bool {
+    self.root.is_match(text)
+  }
+
+  /// Terms that contribute to a match, excluding negated terms
+  pub fn positive_terms(&self) -> Vec<&QueryTerm> {
+    let mut terms = vec![];
+    self.root.collect_terms(false, true, &mut terms);
+    terms
+  }
+
+  /// Negated terms, that must not match
+  pub fn negative_terms(&self) -> Vec<&QueryTerm> {
+    let mut terms = vec![];
+    self.root.collect_terms(false, false, &mut terms);
+    terms
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum QueryToken {
+  Word(String),
+  Phrase(Vec<String>),
+  And,
+  Or,
+  Not,
+  Minus,
+  Open,
+  Close,
+}
+
+impl QueryToken {
+  fn label(&self) -> String {
+    match self {
+      QueryToken::Word(word) => word.to_owned(),
+      QueryToken::Phrase(words) => ["\"", &words.join(" "), "\""].concat(),
+      QueryToken::And => "AND".to_owned(),
+      QueryToken::Or => "OR".to_owned(),
+      QueryToken::Not => "NOT".to_owned(),
+      QueryToken::Minus => "-".to_owned(),
+      QueryToken::Open => "(".to_owned(),
+      QueryToken::Close => ")".to_owned(),
+    }
+  }
+}
+
+/// Split a query into tokens with their byte offsets
+fn tokenize_query(query: &str) -> Result<Vec<(usize, QueryToken)>, QueryError> {
+  let mut tokens: Vec<(usize, QueryToken)> = vec![];
+  let mut chars = query.char_indices().peekable();
+  while let Some((position, ch)) = chars.next() {
+    match ch {
+      '(' => tokens.push((position, QueryToken::Open)),
+      ')' => tokens.push((position, QueryToken::Close)),
+      '"' => {
+        let start = position + 1;
+        let mut end = None;
+        for (next_position, next_char) in chars.by_ref() {
+          if next_char == '"' {
+            end = Some(next_position);
+            break;
+          }
+        }
+        let end = end.ok_or(QueryError::UnterminatedPhrase(position))?;
+        let words: Vec<String> = query[start..end].split_whitespace().map(|word| word.to_owned()).collect();
+        if words.is_empty() {
+          return Err(QueryError::EmptyPhrase(position));
+        }
+        tokens.push((position, QueryToken::Phrase(words)));
+      },
+      '-' => {
+        // a minus sign must be directly followed by the term it excludes
+        if !chars.peek().is_some_and(|(_, next_char)| !next_char.is_whitespace() && *next_char != ')') {
+          return Err(QueryError::MissingOperand(position));
+        }
+        tokens.push((position, QueryToken::Minus));
+      },
+      _ if ch.is_whitespace() => {},
+      _ => {
+        let mut end = query.len();
+        while let Some((next_position, next_char)) = chars.peek() {
+          if next_char.is_whitespace() || matches!(next_char, '(' | ')' | '"') {
+            end = *next_position;
+            break;
+          }
+          chars.next();
+        }
+        let token = match &query[position..end] {
+          "AND" => QueryToken::And,
+          "OR" => QueryToken::Or,
+          "NOT" => QueryToken::Not,
+          word => QueryToken::Word(word.to_owned()),
+        };
+        tokens.push((position, token));
+      },
+    }
+  }
+  Ok(tokens)
+}
+
+/// Recursive descent parser over query tokens
+struct QueryParser {
+  tokens: Vec<(usize, QueryToken)>,
+  index: usize,
+  end: usize,
+  case_insensitive: bool,
+}
+
+impl QueryParser {
+  fn peek(&self) -> Option<&QueryToken> {
+    self.tokens.get(self.index).map(|(_, token)| token)
+  }
+
+  fn position(&self) -> usize {
+    self.tokens.get(self.index).map_or(self.end, |(position, _)| *position)
+  }
+
+  /// Returns an error unless the next token can start an operand
+  fn expect_operand(&self, operator_position: usize) -> Result<(), QueryError> {
+    match self.peek() {
+      None | Some(QueryToken::Close) | Some(QueryToken::And) | Some(QueryToken::Or) => Err(QueryError::MissingOperand(operator_position)),
+      _ => Ok(()),
+    }
+  }
+
+  fn parse_or(&mut self) -> Result<QueryNode, QueryError> {
+    let mut nodes = vec![self.parse_and()?];
+    while self.peek() == Some(&QueryToken::Or) {
+      let operator_position = self.position();
+      self.index += 1;
+      self.expect_operand(operator_position)?;
+      nodes.push(self.parse_and()?);
+    }
+    Ok(if nodes.len() == 1 { nodes.remove(0) } else { QueryNode::Or(nodes) })
+  }
+
+  fn parse_and(&mut self) -> Result<QueryNode, QueryError> {
+    let mut nodes = vec![self.parse_unary()?];
+    loop {
+      match self.peek() {
+        None | Some(QueryToken::Close) | Some(QueryToken::Or) => break,
+        Some(QueryToken::And) => {
+          let operator_position = self.position();
+          self.index += 1;
+          self.expect_operand(operator_position)?;
+        },
+        _ => {},
+      }
+      nodes.push(self.parse_unary()?);
+    }
+    Ok(if nodes.len() == 1 { nodes.remove(0) } else { QueryNode::And(nodes) })
+  }
+
+  fn parse_unary(&mut self) -> Result<QueryNode, QueryError> {
+    if matches!(self.peek(), Some(QueryToken::Not) | Some(QueryToken::Minus)) {
+      let operator_position = self.position();
+      self.index += 1;
+      self.expect_operand(operator_position)?;
+      return Ok(QueryNode::Not(Box::new(self.parse_unary()?)));
+    }
+    self.parse_primary()
+  }
+
+  fn parse_primary(&mut self) -> Result<QueryNode, QueryError> {
+    let position = self.position();
+    let Some((_, token)) = self.tokens.get(self.index).cloned() else {
+      return Err(QueryError::MissingOperand(position));
+    };
+    self.index += 1;
+    match token {
+      QueryToken::Word(word) => Ok(QueryNode::Term(QueryTerm::new(vec![word], false, self.case_insensitive)?)),
+      QueryToken::Phrase(words) => Ok(QueryNode::Term(QueryTerm::new(words, true, self.case_insensitive)?)),
+      QueryToken::Open => {
+        self.expect_operand(position).map_err(|_| QueryError::UnexpectedToken(self.position(), self.peek().map_or("end of query".to_owned(), |token| token.label())))?;
+        let node = self.parse_or()?;
+        if self.peek() != Some(&QueryToken::Close) {
+          return Err(QueryError::UnclosedParenthesis(position));
+        }
+        self.index += 1;
+        Ok(node)
+      },
+      _ => Err(QueryError::UnexpectedToken(position, token.label())),
+    }
+  }
+}
+
+/// True if a word boundary may be placed next to the character, i.e. if it is a word character or wildcard
+fn is_word_edge(ch: Option<char>) -> bool {
+  ch.is_some_and(|ch| is_word_char(ch) || ch == '*' || ch == '?')
+}
+
+/// Convert a word with optional * and ? wildcards to a pattern with other characters escaped
+pub(crate) fn build_term_pattern(word: &str) -> String {
+  let mut pattern = String::with_capacity(word.len() + 8);
+  let mut literal = String::new();
+  for ch in word.chars() {
+    let wildcard = match ch {
+      '*' => r#"\w*"#,
+      '?' => r#"\w"#,
+      _ => {
+        literal.push(ch);
+        continue;
+      }
+    };
+    pattern.push_str(&escape_literal(&literal));
+    literal.clear();
+    pattern.push_str(wildcard);
+  }
+  pattern.push_str(&escape_literal(&literal));
+  pattern
+}
+
+/// Whole-word pattern for one or more words separated by any whitespace.
+/// Word boundaries are only added at word-character edges, so terms such as c++ or .net can be matched too
+fn build_phrase_pattern(words: &[String]) -> String {
+  let inner = words.iter().map(|word| build_term_pattern(word)).collect::<Vec<String>>().join(r#"\s+"#);
+  let first_char = words.first().and_then(|word| word.chars().next());
+  let last_char = words.last().and_then(|word| word.chars().last());
+  let start = if is_word_edge(first_char) { r#"\b"# } else { "" };
+  let end = if is_word_edge(last_char) { r#"\b"# } else { "" };
+  [start, &inner, end].concat()
+}
+
+/// Boolean search query methods for str and String, e.g. "cat AND (dog OR mouse) -fish"
+/// Only match_query_result needs to be implemented. Queries used repeatedly should be compiled once with SearchQuery::new
+pub trait MatchQuery {
+  /// Matches a search query with a case_insensitive flag or returns a QueryError if the query is invalid
+  fn match_query_result(&self, query: &str, case_insensitive: bool) -> Result<bool, QueryError>;
+
+  /// Matches a search query with a case_insensitive flag. NB: If the query is invalid it will return false
+  fn match_query(&self, query: &str, case_insensitive: bool) -> bool {
+    self.match_query_result(query, case_insensitive).unwrap_or(false)
+  }
+
+  /// Matches a search query in case-insensitive mode
+  fn match_query_ci(&self, query: &str) -> bool {
+    self.match_query(query, true)
+  }
+
+  /// Matches a search query in case-sensitive mode
+  fn match_query_cs(&self, query: &str) -> bool {
+    self.match_query(query, false)
+  }
+}
+
+impl MatchQuery for str {
+  fn match_query_result(&self, query: &str, case_insensitive: bool) -> Result<bool, QueryError> {
+    SearchQuery::new(query, case_insensitive).map(|search_query| search_query.is_match(self))
+  }
+}
+
+/// Methods to filter arrays or vectors of strings by boolean search queries.
+/// Each query is parsed and compiled only once. If the query is invalid, filter_query will not be applied,
+/// as with pattern_filter, while filter_query_result returns the error
+pub trait QueryFilter<'a, T> where T:Sized {
+  /// Filters strings by a compiled search query
+  fn filter_search_query(&'a self, search_query: &SearchQuery) -> Vec<T>;
+
+  /// Filters strings by a search query with a case_insensitive flag or returns a QueryError if the query is invalid
+  fn filter_query_result(&'a self, query: &str, case_insensitive: bool) -> Result<Vec<T>, QueryError> {
+    SearchQuery::new(query, case_insensitive).map(|search_query| self.filter_search_query(&search_query))
+  }
+
+  /// Filters strings by a search query with a case_insensitive flag
+  fn filter_query(&'a self, query: &str, case_insensitive: bool) -> Vec<T>;
+
+  /// Filters strings by a search query in case-insensitive mode
+  fn filter_query_ci(&'a self, query: &str) -> Vec<T> {
+    self.filter_query(query, true)
+  }
+
+  /// Filters strings by a search query in case-sensitive mode
+  fn filter_query_cs(&'a self, query: &str) -> Vec<T> {
+    self.filter_query(query, false)
+  }
+}
+
+impl<'a> QueryFilter<'a, String> for [String] {
+  fn filter_search_query(&'a self, search_query: &SearchQuery) -> Vec<String> {
+    self.iter().filter(|item| search_query.is_match(item)).map(|item| item.to_owned()).collect()
+  }
+
+  fn filter_query(&'a self, query: &str, case_insensitive: bool) -> Vec<String> {
+    self.filter_query_result(query, case_insensitive).unwrap_or_else(|_| self.to_owned())
+  }
+}
+
+impl<'a> QueryFilter<'a, &'a str> for [&'a str] {
+  fn filter_search_query(&'a self, search_query: &SearchQuery) -> Vec<&'a str> {
+    self.iter().filter(|item| search_query.is_match(item)).copied().collect()
+  }
+
+  fn filter_query(&'a self, query: &str, case_insensitive: bool) -> Vec<&'a str> {
+    self.filter_query_result(query, case_insensitive).unwrap_or_else(|_| self.to_vec())
+  }
+}
@@ -0,0 +1,179 @@
+use std::cmp::Ordering;
+use crate::engine::{Error, Match, Regex, RegexExt};
+use crate::utils::{build_regex, build_whole_word_pattern};
+use crate::words::count_words_between;
+
+// Lightweight relevance ranking for in-memory collections.
+// Each term is a word pattern with a weight. Items score for every whole-word and partial hit in each field,
+// multiplied by the field weight, plus a bonus when consecutive terms occur close to each other.
+
+/// Weights applied to term hits when scoring strings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScoreWeights {
+  /// score per whole-word hit
+  pub whole_word: f64,
+  /// score per partial hit within a longer word
+  pub partial: f64,
+  /// maximum bonus for consecutive terms as whole words in the same field, awarded in full to adjacent words
+  pub proximity: f64,
+  /// maximum number of intervening words for the proximity bonus
+  pub max_distance: usize,
+}
+
+impl Default for ScoreWeights {
+  fn default() -> Self {
+    ScoreWeights { whole_word: 1.0, partial: 0.5, proximity: 1.0, max_distance: 5 }
+  }
+}
+
+/// Item with its index in the source collection, relevance score and the terms that matched
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoredItem<T> {
+  pub index: usize,
+  pub item: T,
+  pub score: f64,
+  pub matched_terms: Vec<String>,
+}
+
+/// Provides the searchable text fields of an item with their weights.
+/// Implemented for String and &str as a single field with a weight of 1.0. Implement it for custom structs
+/// to rank them by several fields, e.g. a title with a weight of 2.0 and a description with a weight of 1.0
+pub trait WeightedFields {
+  fn weighted_fields(&self) -> Vec<(&str, f64)>;
+}
+
+impl WeightedFields for String {
+  fn weighted_fields(&self) -> Vec<(&str, f64)> {
+    vec![(self.as_str(), 1.0)]
+  }
+}
+
+impl WeightedFields for &str {
+  fn weighted_fields(&self) -> Vec<(&str, f64)> {
+    vec![(self, 1.0)]
+  }
+}
+
+/// Term with whole-word and partial regexes compiled once for a whole collection
+struct CompiledTerm<'t> {
+  term: &'t str,
+  weight: f64,
+  whole_word: Regex,
+  partial: Regex,
+}
+
+/// Scores texts against a set of weighted terms
+struct TermScorer<'t> {
+  terms: Vec<CompiledTerm<'t>>,
+  word_regex: Regex,
+  weights: ScoreWeights,
+}
+
+impl<'t> TermScorer<'t> {
+  fn new(terms: &[(&'t str, f64)], weights: &ScoreWeights, case_insensitive: bool) -> Result<TermScorer<'t>, Error> {
+    let mut compiled_terms = Vec::with_capacity(terms.len());
+    for (term, weight) in terms {
+      let whole_word = build_regex(&build_whole_word_pattern(term), case_insensitive)?;
+      let partial = build_regex(term, case_insensitive)?;
+      compiled_terms.push(CompiledTerm { term, weight: *weight, whole_word, partial });
+    }
+    let word_regex = build_regex(r#"\w+"#, false)?;
+    Ok(TermScorer { terms: compiled_terms, word_regex, weights: *weights })
+  }
+
+  /// Scores a single field, flagging matched terms by their index
+  fn score_field(&self, text: &str, matched: &mut [bool]) -> f64 {
+    let mut score = 0.0;
+    let mut previous_hits: Option<Vec<Match>> = None;
+    let mut words: Option<Vec<Match>> = None;
+    for (index, term) in self.terms.iter().enumerate() {
+      let whole_word_hits: Vec<Match> = term.whole_word.find_all(text).collect();
+      let num_partial = term.partial.find_all(text).count().saturating_sub(whole_word_hits.len());
+      let term_score = whole_word_hits.len() as f64 * self.weights.whole_word + num_partial as f64 * self.weights.partial;
+      if term_score > 0.0 {
+        matched[index] = true;
+        score += term.weight * term_score;
+      }
+      if whole_word_hits.is_empty() {
+        previous_hits = None;
+        continue;
+      }
+      if let Some(previous) = &previous_hits {
+        let words = words.get_or_insert_with(|| self.word_regex.find_all(text).collect());
+        if let Some(distance) = min_word_distance(words, previous, &whole_word_hits) {
+          if distance <= self.weights.max_distance {
+            let closeness = (self.weights.max_distance + 1 - distance) as f64 / (self.weights.max_distance + 1) as f64;
+            score += self.weights.proximity * closeness;
+          }
+        }
+      }
+      previous_hits = Some(whole_word_hits);
+    }
+    score
+  }
+
+  /// Scores all weighted fields of an item, returning None if no term matched
+  fn score_item<T>(&self, index: usize, item: &T) -> Option<ScoredItem<T>> where T: WeightedFields + Clone {
+    let mut matched = vec![false; self.terms.len()];
+    let score = item.weighted_fields().into_iter()
+      .map(|(text, field_weight)| field_weight * self.score_field(text, &mut matched))
+      .sum::<f64>();
+    if score <= 0.0 {
+      return None;
+    }
+    let matched_terms = self.terms.iter().zip(matched).filter(|(_, is_matched)| *is_matched).map(|(term, _)| term.term.to_owned()).collect();
+    Some(ScoredItem { index, item: item.clone(), score, matched_terms })
+  }
+}
+
+/// Smallest number of words between any pair of non-overlapping hits in either order
+fn min_word_distance(words: &[Match], first_hits: &[Match], second_hits: &[Match]) -> Option<usize> {
+  first_hits.iter().flat_map(|first| second_hits.iter().filter_map(move |second| {
+    if first.end() <= second.start() {
+      Some(count_words_between(words, first.end(), second.start()))
+    } else if second.end() <= first.start() {
+      Some(count_words_between(words, second.end(), first.start()))
+    } else {
+      None
+    }
+  })).min()
+}
+
+/// Methods to rank arrays or vectors of strings, or custom types with weighted fields, by word and pattern hits.
+/// Terms are regular expressions as in MatchWord methods. Items without any hits are excluded and the remainder
+/// are sorted by descending score, with ties kept in their original order.
+/// Only rank_by_terms_result needs to be implemented
+pub trait RankByWords<T> {
+  /// Ranks items by weighted terms or returns an error if any term is not a valid regular expression
+  fn rank_by_terms_result(&self, terms: &[(&str, f64)], weights: &ScoreWeights, case_insensitive: bool) -> Result<Vec<ScoredItem<T>>, Error>;
+
+  /// Ranks items by weighted terms. Returns an empty vector if any term is not a valid regular expression
+  fn rank_by_terms(&self, terms: &[(&str, f64)], weights: &ScoreWeights, case_insensitive: bool) -> Vec<ScoredItem<T>> {
+    self.rank_by_terms_result(terms, weights, case_insensitive).unwrap_or_default()
+  }
+
+  /// Ranks items by words of equal weight with the default score weights
+  fn rank_by_words(&self, words: &[&str], case_insensitive: bool) -> Vec<ScoredItem<T>> {
+    let terms: Vec<(&str, f64)> = words.iter().map(|word| (*word, 1.0)).collect();
+    self.rank_by_terms(&terms, &ScoreWeights::default(), case_insensitive)
+  }
+
+  /// Ranks items by words of equal weight in case-insensitive mode
+  fn rank_by_words_ci(&self, words: &[&str]) -> Vec<ScoredItem<T>> {
+    self.rank_by_words(words, true)
+  }
+
+  /// Ranks items by words of equal weight in case-sensitive mode
+  fn rank_by_words_cs(&self, words: &[&str]) -> Vec<ScoredItem<T>> {
+    self.rank_by_words(words, false)
+  }
+}
+
+impl<T> RankByWords<T> for [T] where T: WeightedFields + Clone {
+  fn rank_by_terms_result(&self, terms: &[(&str, f64)], weights: &ScoreWeights, case_insensitive: bool) -> Result<Vec<ScoredItem<T>>, Error> {
+    let scorer = TermScorer::new(terms, weights, case_insensitive)?;
+    let mut scored_items: Vec<ScoredItem<T>> = self.iter().enumerate().filter_map(|(index, item)| scorer.score_item(index, item)).collect();
+    scored_items.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+    Ok(scored_items)
+  }
+}
@@ -0,0 +1,64 @@
+use unicode_segmentation::UnicodeSegmentation;
+use crate::engine::{Error, Match, Regex, RegexExt};
+use crate::enums::WordBounds;
+use crate::utils::build_regex;
+
+// Word methods based on Unicode word segmentation (UAX #29) rather than \b, used by the _segmented methods
+// of MatchWord, ReplaceWord, PatternCapture and PatternFilter.
+// Text is split into word tokens with unicode-segmentation, which keeps contractions such as can't and
+// decimal numbers such as 3.14 together, splits ideographs into single-character words and ignores emoji,
+// spaces and punctuation. A word pattern is then matched against each token, so patterns cannot span tokens.
+// UAX #29 does not split scripts written without spaces between words, such as Thai, Lao or Khmer, which would
+// need dictionary-based segmentation, so a run of Thai letters is a single token.
+
+/// Yields the UAX #29 word tokens with their byte offsets, excluding whitespace, punctuation and emoji
+pub fn segment_words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+  text.unicode_word_indices()
+}
+
+/// Compiles a word pattern to be matched against single tokens. With WordBounds::Both the pattern must
+/// match the whole token, with Start or End only the start or end of the token, and with None any part of it
+fn build_token_regex(word: &str, bounds: WordBounds, case_insensitive: bool) -> Result<Regex, Error> {
+  let pattern = match bounds {
+    WordBounds::Both => ["^(?:", word, ")$"].concat(),
+    WordBounds::Start => ["^(?:", word, ")"].concat(),
+    WordBounds::End => ["(?:", word, ")$"].concat(),
+    WordBounds::None => ["(?:", word, ")"].concat(),
+  };
+  build_regex(&pattern, case_insensitive)
+}
+
+/// Finds the parts of word tokens matching the word pattern with bounds, with offsets in the text
+pub(crate) fn find_segmented_words<'t>(text: &'t str, word: &str, bounds: WordBounds, case_insensitive: bool) -> Result<Vec<Match<'t>>, Error> {
+  let re = build_token_regex(word, bounds, case_insensitive)?;
+  let mut matched_items: Vec<Match<'t>> = vec![];
+  for (start, token) in segment_words(text) {
+    matched_items.extend(re.find_all(token).map(|matched_item| Match::new(matched_item.as_str(), start + matched_item.start())));
+  }
+  Ok(matched_items)
+}
+
+/// Replaces the parts of word tokens matching the word pattern with bounds, expanding capture groups in the replacement
+pub(crate) fn replace_segmented_words(text: &str, word: &str, replacement: &str, bounds: WordBounds, case_insensitive: bool) -> Result<String, Error> {
+  let re = build_token_regex(word, bounds, case_insensitive)?;
+  let mut output = String::with_capacity(text.len());
+  let mut position = 0;
+  for (start, token) in segment_words(text) {
+    if re.matches_text(token) {
+      output.push_str(&text[position..start]);
+      output.push_str(&re.replace_all_result(token, replacement)?);
+      position = start + token.len();
+    }
+  }
+  output.push_str(&text[position..]);
+  Ok(output)
+}
+
+/// Filters texts with a word token matching the word pattern with bounds.
+/// The pattern is compiled only once and nothing is filtered if it is not a valid regular expression
+pub(crate) fn filter_segmented_words<T: AsRef<str> + Clone>(texts: &[T], word: &str, bounds: WordBounds, case_insensitive: bool) -> Vec<T> {
+  match build_token_regex(word, bounds, case_insensitive) {
+    Ok(re) => texts.iter().filter(|text| text.as_ref().unicode_words().any(|token| re.matches_text(token))).cloned().collect(),
+    Err(_) => texts.to_vec(),
+  }
+}
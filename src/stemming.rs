@@ -0,0 +1,174 @@
+use rust_stemmers::{Algorithm, Stemmer};
+use crate::utils::is_word_char;
+
+// Word matching on Snowball stems rather than spellings.
+// Text is split into words as runs of letters, digits and underscores, as with \w, keeping apostrophes between
+// word characters so can't is not split into can and t. Each word is lower-cased and reduced to its stem,
+// so run, runs and running all share the stem run while runway does not.
+// Stems are always compared case-insensitively. Word arguments are plain words rather than regular
+// expressions and may contain several words to match a phrase of consecutive words with the same stems.
+// Matches are reported with the surface form as it appears in the text.
+
+/// Languages with Snowball stemming algorithms, e.g. StemLanguage::English for the Porter2 stemmer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StemLanguage {
+  Arabic,
+  Danish,
+  Dutch,
+  English,
+  Finnish,
+  French,
+  German,
+  Greek,
+  Hungarian,
+  Italian,
+  Norwegian,
+  Portuguese,
+  Romanian,
+  Russian,
+  Spanish,
+  Swedish,
+  Tamil,
+  Turkish,
+}
+
+impl StemLanguage {
+  fn to_algorithm(self) -> Algorithm {
+    match self {
+      StemLanguage::Arabic => Algorithm::Arabic,
+      StemLanguage::Danish => Algorithm::Danish,
+      StemLanguage::Dutch => Algorithm::Dutch,
+      StemLanguage::English => Algorithm::English,
+      StemLanguage::Finnish => Algorithm::Finnish,
+      StemLanguage::French => Algorithm::French,
+      StemLanguage::German => Algorithm::German,
+      StemLanguage::Greek => Algorithm::Greek,
+      StemLanguage::Hungarian => Algorithm::Hungarian,
+      StemLanguage::Italian => Algorithm::Italian,
+      StemLanguage::Norwegian => Algorithm::Norwegian,
+      StemLanguage::Portuguese => Algorithm::Portuguese,
+      StemLanguage::Romanian => Algorithm::Romanian,
+      StemLanguage::Russian => Algorithm::Russian,
+      StemLanguage::Spanish => Algorithm::Spanish,
+      StemLanguage::Swedish => Algorithm::Swedish,
+      StemLanguage::Tamil => Algorithm::Tamil,
+      StemLanguage::Turkish => Algorithm::Turkish,
+    }
+  }
+
+  /// Creates the Snowball stemmer for this language
+  fn stemmer(self) -> Stemmer {
+    Stemmer::create(self.to_algorithm())
+  }
+}
+
+/// Returns the lower-case stem of a single word
+pub fn stem_word(word: &str, language: StemLanguage) -> String {
+  language.stemmer().stem(&word.to_lowercase()).into_owned()
+}
+
+/// Yields words as runs of word characters with their byte offsets.
+/// An apostrophe between word characters, as in can't or O’Brien, is part of the word
+fn word_tokens(text: &str) -> Vec<(usize, &str)> {
+  let mut tokens: Vec<(usize, &str)> = vec![];
+  let mut token_start: Option<usize> = None;
+  let mut chars = text.char_indices().peekable();
+  while let Some((index, ch)) = chars.next() {
+    let is_inner_apostrophe = matches!(ch, '\'' | '’') && token_start.is_some() && chars.peek().is_some_and(|(_, next_ch)| is_word_char(*next_ch));
+    match (is_word_char(ch) || is_inner_apostrophe, token_start) {
+      (true, None) => token_start = Some(index),
+      (false, Some(start)) => {
+        tokens.push((start, &text[start..index]));
+        token_start = None;
+      },
+      _ => (),
+    }
+  }
+  if let Some(start) = token_start {
+    tokens.push((start, &text[start..]));
+  }
+  tokens
+}
+
+/// Stems of the words in a word or phrase
+fn phrase_stems(phrase: &str, stemmer: &Stemmer) -> Vec<String> {
+  word_tokens(phrase).into_iter().map(|(_, token)| stemmer.stem(&token.to_lowercase()).into_owned()).collect()
+}
+
+/// Finds runs of consecutive words whose stems equal the target stems, with their byte offsets
+fn find_stem_matches<'t>(text: &'t str, target_stems: &[String], stemmer: &Stemmer) -> Vec<(usize, &'t str)> {
+  if target_stems.is_empty() {
+    return vec![];
+  }
+  let tokens = word_tokens(text);
+  let stems: Vec<String> = tokens.iter().map(|(_, token)| stemmer.stem(&token.to_lowercase()).into_owned()).collect();
+  let mut matched_items: Vec<(usize, &'t str)> = vec![];
+  let mut index = 0;
+  while index + target_stems.len() <= stems.len() {
+    if stems[index..index + target_stems.len()] == *target_stems {
+      let (start, _) = tokens[index];
+      let (last_start, last_token) = tokens[index + target_stems.len() - 1];
+      matched_items.push((start, &text[start..last_start + last_token.len()]));
+      index += target_stems.len();
+    } else {
+      index += 1;
+    }
+  }
+  matched_items
+}
+
+/// Methods to match words or phrases in strings by their stems.
+/// Only find_word_stems needs to be implemented
+pub trait MatchStem<'a> {
+  /// Yields the surface forms of words or phrases sharing the stems of the given word or phrase, with their byte offsets
+  fn find_word_stems(&'a self, word: &str, language: StemLanguage) -> Vec<(usize, &'a str)>;
+
+  /// Matches any form of a word or phrase sharing the same stems, e.g. runs or running for run
+  fn match_word_stem(&'a self, word: &str, language: StemLanguage) -> bool {
+    !self.find_word_stems(word, language).is_empty()
+  }
+
+  /// Matches if any of the words or phrases occurs in any form sharing the same stems
+  fn match_any_word_stems(&'a self, words: &[&str], language: StemLanguage) -> bool {
+    words.iter().any(|word| self.match_word_stem(word, language))
+  }
+
+  /// Matches if all of the words or phrases occur in any form sharing the same stems
+  fn match_word_stems(&'a self, words: &[&str], language: StemLanguage) -> bool {
+    words.iter().all(|word| self.match_word_stem(word, language))
+  }
+
+  /// Counts the occurrences of any form of a word or phrase sharing the same stems
+  fn count_word_stem(&'a self, word: &str, language: StemLanguage) -> usize {
+    self.find_word_stems(word, language).len()
+  }
+}
+
+impl<'a> MatchStem<'a> for str {
+  fn find_word_stems(&'a self, word: &str, language: StemLanguage) -> Vec<(usize, &'a str)> {
+    let stemmer = language.stemmer();
+    find_stem_matches(self, &phrase_stems(word, &stemmer), &stemmer)
+  }
+}
+
+/// Methods to filter arrays or vectors of strings by word stems. The word or phrase is stemmed only once
+pub trait StemFilter<'a, T> {
+  /// Filters strings containing any form of the word or phrase sharing the same stems
+  fn pattern_filter_word_stem(&'a self, word: &str, language: StemLanguage) -> Vec<T>;
+}
+
+impl<'a> StemFilter<'a, String> for [String] {
+  fn pattern_filter_word_stem(&'a self, word: &str, language: StemLanguage) -> Vec<String> {
+    let stemmer = language.stemmer();
+    let target_stems = phrase_stems(word, &stemmer);
+    self.iter().filter(|text| !find_stem_matches(text, &target_stems, &stemmer).is_empty()).cloned().collect()
+  }
+}
+
+impl<'a> StemFilter<'a, &'a str> for [&'a str] {
+  fn pattern_filter_word_stem(&'a self, word: &str, language: StemLanguage) -> Vec<&'a str> {
+    let stemmer = language.stemmer();
+    let target_stems = phrase_stems(word, &stemmer);
+    self.iter().filter(|text| !find_stem_matches(text, &target_stems, &stemmer).is_empty()).copied().collect()
+  }
+}
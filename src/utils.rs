@@ -1,12 +1,12 @@
-use regex::{Regex, Error};
+use crate::engine::{escape_literal, Regex, Error};
 use crate::enums::WordBounds;
 
-/// Build a regular expression with an optional case-insenistive non-capturing group
-/// If the source pattern starts with a non-capturing group, this will be ignored irrespective of the case_insenistive flag
+/// Build a regular expression with an optional case-insenistive flag
+/// Flags set at the start of the source pattern, e.g. (?-i), still override the case_insenistive flag
 pub fn build_regex(pattern: &str, case_insensitive: bool) -> Result<Regex, Error> {
   let mut parts: Vec<&str> = vec![];
-  // do not case-insensitive flag if a similar flag is already in the regular expression
-  if case_insensitive && pattern.starts_with("(?") == false {
+  // the flag is added even before a non-capturing group such as (?:a|b), as later flags override it anyway
+  if case_insensitive {
     parts.push("(?i)");
   }
   parts.push(pattern);
@@ -14,6 +14,137 @@ pub fn build_regex(pattern: &str, case_insensitive: bool) -> Result<Regex, Error
   Regex::new(&regex_str)
 }
 
+/// Character class matching ASCII and Unicode whitespace including no-break spaces and line separators,
+/// as \s only matches ASCII whitespace with regex-lite
+const FLEX_SPACE_CLASS: &str = "[\\s\u{85}\u{a0}\u{1680}\u{2000}-\u{200a}\u{2028}\u{2029}\u{202f}\u{205f}\u{3000}]";
+
+/// Rewrites a pattern so that each run of whitespace matches any run of whitespace, including line breaks,
+/// tabs and no-break spaces. Escaped whitespace and whitespace within character classes are kept as they are.
+/// Patterns setting the x flag, e.g. (?x) or (?ix:...), ignore whitespace and are returned unchanged
+pub fn build_flex_space_pattern(pattern: &str) -> String {
+  if sets_verbose_flag(pattern) {
+    return pattern.to_owned();
+  }
+  let mut output = String::with_capacity(pattern.len() + 16);
+  let mut chars = pattern.chars().peekable();
+  let mut class_depth = 0;
+  while let Some(ch) = chars.next() {
+    match ch {
+      '\\' => {
+        output.push(ch);
+        if let Some(next_ch) = chars.next() {
+          output.push(next_ch);
+        }
+      },
+      '[' => {
+        output.push(ch);
+        class_depth += 1;
+        // a leading ] or ^] is a literal bracket
+        if chars.peek() == Some(&'^') {
+          output.push('^');
+          chars.next();
+        }
+        if chars.peek() == Some(&']') {
+          output.push(']');
+          chars.next();
+        }
+      },
+      ']' if class_depth > 0 => {
+        output.push(ch);
+        class_depth -= 1;
+      },
+      _ if ch.is_whitespace() && class_depth == 0 => {
+        while chars.next_if(|next_ch| next_ch.is_whitespace()).is_some() {}
+        // a quantifier after the space applies to the whole run
+        if matches!(chars.peek(), Some('*' | '+' | '?' | '{')) {
+          output.push_str(&["(?:", FLEX_SPACE_CLASS, "+)"].concat());
+        } else {
+          output.push_str(FLEX_SPACE_CLASS);
+          output.push('+');
+        }
+      },
+      _ => output.push(ch),
+    }
+  }
+  output
+}
+
+/// Checks if a flag group such as (?x), (?ix) or (?x:...) enables whitespace-insensitive mode
+fn sets_verbose_flag(pattern: &str) -> bool {
+  pattern.match_indices("(?").any(|(index, _)| {
+    if pattern[..index].ends_with('\\') {
+      return false;
+    }
+    let flags: String = pattern[index + 2..].chars().take_while(|ch| ch.is_ascii_alphabetic() || *ch == '-').collect();
+    let closed = matches!(pattern[index + 2 + flags.len()..].chars().next(), Some(')' | ':'));
+    closed && flags.split('-').next().is_some_and(|enabled| enabled.contains('x'))
+  })
+}
+
+/// Rewrites the literal characters of a pattern, leaving escapes, groups, quantifiers and other syntax as they are.
+/// Runs of literal characters outside character classes are passed to map_literal with a flag set if a quantifier
+/// follows, and literal characters within classes to map_class_char, which returns None to keep a character
+#[cfg_attr(not(any(feature = "folding", feature = "normalization")), allow(dead_code))]
+pub(crate) fn map_pattern_literals(pattern: &str, mut map_literal: impl FnMut(&str, bool) -> String, mut map_class_char: impl FnMut(char) -> Option<String>) -> String {
+  let mut output = String::with_capacity(pattern.len() + 16);
+  let mut chars = pattern.char_indices().peekable();
+  let mut class_depth = 0;
+  let mut literal_start: Option<usize> = None;
+  while let Some((index, ch)) = chars.next() {
+    let is_literal = class_depth == 0 && !matches!(ch, '\\' | '[' | ']' | '(' | ')' | '|' | '.' | '*' | '+' | '?' | '{' | '}' | '^' | '$');
+    if is_literal {
+      literal_start.get_or_insert(index);
+      continue;
+    }
+    if let Some(start) = literal_start.take() {
+      output.push_str(&map_literal(&pattern[start..index], class_depth == 0 && matches!(ch, '*' | '+' | '?' | '{')));
+    }
+    match ch {
+      '\\' => {
+        output.push(ch);
+        if let Some((_, next_ch)) = chars.next() {
+          output.push(next_ch);
+          // escapes with braces such as \p{Greek} or \x{e9}
+          if matches!(next_ch, 'p' | 'P' | 'x' | 'u' | 'U') && chars.peek().is_some_and(|(_, brace)| *brace == '{') {
+            for (_, escape_ch) in chars.by_ref() {
+              output.push(escape_ch);
+              if escape_ch == '}' {
+                break;
+              }
+            }
+          }
+        }
+      },
+      '[' => {
+        output.push(ch);
+        class_depth += 1;
+        // a leading ] or ^] is a literal bracket
+        if let Some((_, negation)) = chars.next_if(|(_, next_ch)| *next_ch == '^') {
+          output.push(negation);
+        }
+        if let Some((_, bracket)) = chars.next_if(|(_, next_ch)| *next_ch == ']') {
+          output.push(bracket);
+        }
+      },
+      ']' if class_depth > 0 => {
+        output.push(ch);
+        class_depth -= 1;
+      },
+      _ if class_depth > 0 && !ch.is_ascii() => {
+        match map_class_char(ch) {
+          Some(mapped) => output.push_str(&mapped),
+          None => output.push(ch),
+        }
+      },
+      _ => output.push(ch),
+    }
+  }
+  if let Some(start) = literal_start {
+    output.push_str(&map_literal(&pattern[start..], false));
+  }
+  output
+}
+
 // internal utility methods
 
 /// build regex pattern with word boundaries and WordBounds options
@@ -27,14 +158,156 @@ pub(crate) fn build_whole_word_pattern(word: &str) -> String {
   build_word_pattern(word, WordBounds::Both)
 }
 
-/// constructs an optional match group for whole words from an array of strs
+/// constructs an optional match group for whole words from an array of regular expressions
 /// e.g. &["cat?", "dog"] will match strings where cat and/or dog appear as whole words.
 /// should be used with build_regex above or pattern_match / pattern_replace
 pub(crate) fn build_optional_whole_word_pattern(words: &[&str]) -> String {
-  let word_pattern = ["(", &words.join("|"), ")"].concat();
+  let word_pattern = ["(?:", &words.join("|"), ")"].concat();
   build_word_pattern(&word_pattern, WordBounds::Both)
 }
 
+/// Builds a pattern matching any of the literal words with word bounds, escaping all regex meta characters.
+/// Words are factored into a prefix trie, e.g. cat, car and cart become ca(?:t|rt??), which keeps patterns
+/// for long word lists compact and fast to match while earlier words keep priority as in a plain alternation.
+/// As with build_literal_word_pattern, word boundaries are only applied at word-character edges, so c++ and .net
+/// can be matched as whole words. Words with and without such edges are grouped separately, in order of first appearance
+pub fn build_word_list_pattern(words: &[&str], bounds: WordBounds) -> String {
+  let mut groups: Vec<(WordEdges, Vec<&str>)> = vec![];
+  for word in words.iter().filter(|word| !word.is_empty()) {
+    let edges = WordEdges::new(word);
+    match groups.iter_mut().find(|(other, _)| *other == edges) {
+      Some((_, group)) => group.push(word),
+      None => groups.push((edges, vec![word])),
+    }
+  }
+  let parts: Vec<String> = groups.iter().map(|(edges, group)| {
+    let word_pattern = ["(?:", &WordTrie::new(group).to_pattern(), ")"].concat();
+    build_word_pattern(&word_pattern, edges.bounds(bounds))
+  }).collect();
+  match parts.as_slice() {
+    [] => build_word_pattern("(?:)", bounds),
+    [part] => part.to_owned(),
+    _ => ["(?:", &parts.join("|"), ")"].concat(),
+  }
+}
+
+/// Builds a pattern matching a word literally, escaping all regex meta characters.
+/// Word boundaries are only added where the word starts or ends with a word character,
+/// because \b next to punctuation would require an adjacent word character, e.g. after c++
+pub(crate) fn build_literal_word_pattern(word: &str, bounds: WordBounds) -> String {
+  build_word_pattern(&escape_literal(word), WordEdges::new(word).bounds(bounds))
+}
+
+/// Word characters as matched by \w: letters, digits and underscores
+pub(crate) fn is_word_char(ch: char) -> bool {
+  ch.is_alphanumeric() || ch == '_'
+}
+
+/// Escapes a character for use within a character class. Unlike escape_literal, this escapes - with all backends
+pub(crate) fn escape_class_char(ch: char) -> String {
+  match ch {
+    '\\' | '[' | ']' | '^' | '-' | '&' | '~' => ['\\', ch].iter().collect(),
+    _ => ch.to_string(),
+  }
+}
+
+/// Escapes $ in a replacement string, so that it is inserted literally without expanding capture groups
+pub(crate) fn escape_replacement(replacement: &str) -> String {
+  replacement.replace('$', "$$")
+}
+
+/// Whether a literal word starts and ends with word characters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct WordEdges {
+  start: bool,
+  end: bool,
+}
+
+impl WordEdges {
+  fn new(word: &str) -> WordEdges {
+    WordEdges {
+      start: word.chars().next().is_some_and(is_word_char),
+      end: word.chars().next_back().is_some_and(is_word_char),
+    }
+  }
+
+  /// Restricts the word bounds to word-character edges
+  fn bounds(&self, bounds: WordBounds) -> WordBounds {
+    let start = self.start && matches!(bounds, WordBounds::Start | WordBounds::Both);
+    let end = self.end && matches!(bounds, WordBounds::End | WordBounds::Both);
+    match (start, end) {
+      (true, true) => WordBounds::Both,
+      (true, false) => WordBounds::Start,
+      (false, true) => WordBounds::End,
+      (false, false) => WordBounds::None,
+    }
+  }
+}
+
+/// Prefix trie of literal words, rendered as a factored regex alternation.
+/// Branches keep the order in which words were added, so that the leftmost-first priority of a plain
+/// alternation is preserved, e.g. car|cart becomes cart?? and cart|car becomes cart?
+#[derive(Default)]
+struct WordTrie {
+  children: Vec<(char, WordTrie)>,
+  /// Position among the branches at which a word ends here, i.e. the number of branches added before it
+  end_position: Option<usize>,
+}
+
+impl WordTrie {
+  fn new(words: &[&str]) -> WordTrie {
+    let mut root = WordTrie::default();
+    for word in words.iter().filter(|word| !word.is_empty()) {
+      let node = word.chars().fold(&mut root, |node, ch| node.child(ch));
+      if node.end_position.is_none() {
+        node.end_position = Some(node.children.len());
+      }
+    }
+    root
+  }
+
+  /// Returns the child for the character, adding it after existing branches if needed
+  fn child(&mut self, ch: char) -> &mut WordTrie {
+    let index = match self.children.iter().position(|(other, _)| *other == ch) {
+      Some(index) => index,
+      None => {
+        self.children.push((ch, WordTrie::default()));
+        self.children.len() - 1
+      },
+    };
+    &mut self.children[index].1
+  }
+
+  /// Renders the subtrie below this node. If a word may also end here, the branches become optional,
+  /// lazily if the shorter word came first, or an empty alternative is placed between the branches
+  fn to_pattern(&self) -> String {
+    let branches: Vec<String> = self.children.iter().map(|(ch, child)| {
+      [escape_literal(&ch.to_string()), child.to_pattern()].concat()
+    }).collect();
+    let quantifier = match self.end_position {
+      Some(0) => "??",
+      Some(_) => "?",
+      None => "",
+    };
+    match (branches.as_slice(), self.end_position) {
+      ([], _) => String::new(),
+      ([branch], None) => branch.to_owned(),
+      ([branch], Some(_)) if is_single_atom(branch) => [branch, quantifier].concat(),
+      (_, Some(position)) if position > 0 && position < branches.len() => {
+        let (before, after) = branches.split_at(position);
+        ["(?:", &before.join("|"), "||", &after.join("|"), ")"].concat()
+      },
+      _ => ["(?:", &branches.join("|"), ")", quantifier].concat(),
+    }
+  }
+}
+
+/// True if an escaped pattern matches a single character, e.g. a or \+
+fn is_single_atom(pattern: &str) -> bool {
+  let mut chars = pattern.chars();
+  matches!((chars.next(), chars.next(), chars.next()), (Some(_), None, _) | (Some('\\'), Some(_), None))
+}
+
 /*
 * Convert an str array to vector of tuple pairs with the second element having the same boolean value
 * as used in many multple match methods where the boolean element indicates case-sensitivity
@@ -1,4 +1,10 @@
-use crate::{PatternReplace, utils::{build_whole_word_pattern, build_word_pattern, build_optional_whole_word_pattern}, WordBounds, PatternMatch, PatternCapture};
+use std::ops::RangeInclusive;
+use crate::boundary::{replace_bounded_words, WordBoundary};
+use crate::inflection::{build_inflected_word_pattern, replace_inflected_words};
+use crate::engine::Match;
+#[cfg(feature = "segmentation")]
+use crate::segmentation::{find_segmented_words, replace_segmented_words, segment_words};
+use crate::{PatternReplace, utils::{build_whole_word_pattern, build_word_pattern, build_optional_whole_word_pattern, build_literal_word_pattern, build_word_list_pattern, build_flex_space_pattern, escape_replacement}, WordBounds, PatternMatch, PatternCapture};
 
 // Set of traits with extension methods to match or replace one or more whole words or sets of whole words
 // with various word boundary and case-sensitivity rules
@@ -19,8 +25,96 @@ pub trait MatchWord<'a> where Self:PatternMatch, Self:PatternCapture<'a> {
     self.pattern_match(&pattern, case_insensitive)
   }
 
-  /// Match any whole words only with a boolean case_insensitive flag
+  /// Match a literal word with bounds options and case_insensitive flag, escaping all regex meta characters.
+  /// Word boundaries only apply at word-character edges, so c++ matches as a whole word in "c++ code"
+  fn match_word_bounds_literal(&self, word: &str, bounds: WordBounds, case_insensitive: bool) -> bool {
+    self.pattern_match(&build_literal_word_pattern(word, bounds), case_insensitive)
+  }
+
+  /// Case-conditional match of a literal whole word, escaping all regex meta characters
+  fn match_word_literal(&self, word: &str, case_insensitive: bool) -> bool {
+    self.match_word_bounds_literal(word, WordBounds::Both, case_insensitive)
+  }
+
+  /// Match a word or phrase with bounds options and case_insensitive flag, where any run of whitespace
+  /// in the word matches any run of whitespace in the text, including line breaks and no-break spaces
+  fn match_word_bounds_flex_space(&self, word: &str, bounds: WordBounds, case_insensitive: bool) -> bool {
+    self.pattern_match(&build_word_pattern(&build_flex_space_pattern(word), bounds), case_insensitive)
+  }
+
+  /// Case-conditional match of a whole word or phrase, where any run of whitespace matches any run of whitespace
+  fn match_word_flex_space(&self, word: &str, case_insensitive: bool) -> bool {
+    self.match_word_bounds_flex_space(word, WordBounds::Both, case_insensitive)
+  }
+
+  /// Match any English inflection of a whole word with a case_insensitive flag,
+  /// e.g. mouse, mice, run, runs, running or ran. The word is matched literally
+  fn match_word_inflected(&self, word: &str, case_insensitive: bool) -> bool {
+    self.pattern_match(&build_inflected_word_pattern(word), case_insensitive)
+  }
+
+  /// Match any English inflection of a whole word in case-insensitive mode
+  fn match_word_inflected_ci(&self, word: &str) -> bool {
+    self.match_word_inflected(word, true)
+  }
+
+  /// Match any literal whole words with a boolean case_insensitive flag, escaping all regex meta characters
+  fn match_any_words_literal(&self, words: &[&str], case_insensitive: bool) -> bool {
+    self.pattern_match(&build_word_list_pattern(words, WordBounds::Both), case_insensitive)
+  }
+
+  /// Match all literal whole words with a boolean case_insensitive flag, escaping all regex meta characters
+  fn match_words_literal(&self, words: &[&str], case_insensitive: bool) -> bool {
+    words.iter().all(|word| self.match_word_literal(word, case_insensitive))
+  }
+
+  /// Match a word with bounds and a custom word boundary definition instead of \b, with a case_insensitive flag,
+  /// e.g. to match e-mail but not mail with WordBoundary::new().with_hyphens()
+  fn match_word_boundary(&self, word: &str, bounds: WordBounds, boundary: &WordBoundary, case_insensitive: bool) -> bool {
+    self.pattern_match(&bounds.to_pattern_with(word, boundary), case_insensitive)
+  }
+
+  /// Yields the Unicode word tokens (UAX #29) with their byte offsets, excluding whitespace, punctuation and emoji
+  #[cfg(feature = "segmentation")]
+  fn segmented_words(&self) -> impl Iterator<Item = (usize, &str)> where Self: AsRef<str> {
+    segment_words(self.as_ref())
+  }
+
+  /// Match a word pattern against Unicode word tokens (UAX #29) instead of \b boundaries, with bounds
+  /// within each token and a case_insensitive flag, e.g. can does not match can't. Returns false if the regex fails
+  #[cfg(feature = "segmentation")]
+  fn match_word_bounds_segmented(&self, word: &str, bounds: WordBounds, case_insensitive: bool) -> bool where Self: AsRef<str> {
+    find_segmented_words(self.as_ref(), word, bounds, case_insensitive).is_ok_and(|matched_items| !matched_items.is_empty())
+  }
+
+  /// Match a whole Unicode word token with a boolean case_insensitive flag
+  #[cfg(feature = "segmentation")]
+  fn match_word_segmented(&self, word: &str, case_insensitive: bool) -> bool where Self: AsRef<str> {
+    self.match_word_bounds_segmented(word, WordBounds::Both, case_insensitive)
+  }
+
+  /// Match a whole Unicode word token in case-insensitive mode
+  #[cfg(feature = "segmentation")]
+  fn match_word_segmented_ci(&self, word: &str) -> bool where Self: AsRef<str> {
+    self.match_word_segmented(word, true)
+  }
+
+  /// Match a whole Unicode word token in case-sensitive mode
+  #[cfg(feature = "segmentation")]
+  fn match_word_segmented_cs(&self, word: &str) -> bool where Self: AsRef<str> {
+    self.match_word_segmented(word, false)
+  }
+
+  /// Match any literal whole words with a boolean case_insensitive flag.
+  /// Regex meta characters are escaped and the words are compiled into a single trie-factored pattern
   fn match_any_words(&self, words: &[&str], case_insensitive: bool) -> bool {
+    let pattern = build_word_list_pattern(words, WordBounds::Both);
+    self.pattern_match(&pattern, case_insensitive)
+  }
+
+  /// Match any whole words given as regular expressions with a boolean case_insensitive flag,
+  /// e.g. colou?r and grey|gray
+  fn match_any_word_patterns(&self, words: &[&str], case_insensitive: bool) -> bool {
     let pattern = build_optional_whole_word_pattern(words);
     self.pattern_match(&pattern, case_insensitive)
   }
@@ -43,16 +137,14 @@ pub trait MatchWord<'a> where Self:PatternMatch, Self:PatternCapture<'a> {
     self.match_word(word, true)
   }
 
-  /// Match any whole words only in case-insensitive mode
+  /// Match any literal whole words only in case-insensitive mode
   fn match_any_words_ci(&self, words: &[&str]) -> bool {
-    let pattern = build_optional_whole_word_pattern(words);
-    self.pattern_match(&pattern, true)
+    self.match_any_words(words, true)
   }
 
-  /// Match any whole words only in case-sensitive mode
+  /// Match any literal whole words only in case-sensitive mode
   fn match_any_words_cs(&self, words: &[&str]) -> bool {
-    let pattern = build_optional_whole_word_pattern(words);
-    self.pattern_match(&pattern, false)
+    self.match_any_words(words, false)
   }
 
   /// Case-insensitive match from the start of a word boundary
@@ -144,6 +236,142 @@ pub trait MatchWord<'a> where Self:PatternMatch, Self:PatternCapture<'a> {
     num_matched == num_words
   }
 
+  /// Finds doubled words separated only by whitespace, e.g. "the the", with a boolean case_insensitive flag
+  /// Each match spans both words. A word repeated three times yields two overlapping matches.
+  /// For other repeated patterns use pattern_matches_backref with the backreferences feature
+  fn find_repeated_words(&'a self, case_insensitive: bool) -> Vec<Match<'a>> where Self: AsRef<str> {
+    let word_pairs = self.pattern_matches_overlapping(r#"\b\w+\s+\w+\b"#, false);
+    word_pairs.into_iter().filter(|pair| {
+      let mut words = pair.as_str().split_whitespace();
+      match (words.next(), words.next()) {
+        (Some(first), Some(second)) => if case_insensitive {
+          first.to_lowercase() == second.to_lowercase()
+        } else {
+          first == second
+        },
+        _ => false
+      }
+    }).collect()
+  }
+
+  /// Finds the first pair of words separated by a number of intervening words within the distance range,
+  /// e.g. 0..=2 matches adjacent words or words with up to two words between them.
+  /// If ordered is true, the first word must precede the second. Words are matched with the given bounds
+  /// and other words are counted as sequences of word characters (\w+).
+  /// If several pairs qualify, the pair starting first and then ending first is selected.
+  /// Returns the matches for the first and second words in that order, whether or not ordered is true
+  fn find_words_by_proximity_bounds(&'a self, first: &str, second: &str, distance: RangeInclusive<usize>, ordered: bool, bounds: WordBounds, case_insensitive: bool) -> Option<(Match<'a>, Match<'a>)> {
+    let first_matches = self.pattern_matches_outer(&bounds.to_pattern(first), case_insensitive);
+    if first_matches.is_empty() {
+      return None;
+    }
+    let second_matches = self.pattern_matches_outer(&bounds.to_pattern(second), case_insensitive);
+    let words = self.pattern_matches_vec(r#"\w+"#, false);
+    let mut selected: Option<(Match<'a>, Match<'a>)> = None;
+    for first_match in &first_matches {
+      for second_match in &second_matches {
+        let (left, right) = if first_match.end() <= second_match.start() {
+          (first_match, second_match)
+        } else if !ordered && second_match.end() <= first_match.start() {
+          (second_match, first_match)
+        } else {
+          continue;
+        };
+        if !distance.contains(&count_words_between(&words, left.end(), right.start())) {
+          continue;
+        }
+        let is_earlier = selected.map_or(true, |(a, b)| {
+          (left.start(), right.end()) < (a.start().min(b.start()), a.end().max(b.end()))
+        });
+        if is_earlier {
+          selected = Some((*first_match, *second_match));
+        }
+      }
+    }
+    selected
+  }
+
+  /// Matches two words separated by a number of intervening words within the distance range,
+  /// with order, bounds and case_insensitive options
+  fn match_words_by_proximity_bounds(&'a self, first: &str, second: &str, distance: RangeInclusive<usize>, ordered: bool, bounds: WordBounds, case_insensitive: bool) -> bool {
+    self.find_words_by_proximity_bounds(first, second, distance, ordered, bounds, case_insensitive).is_some()
+  }
+
+  /// Finds the first pair of whole words in either order with between min and max intervening words
+  fn find_words_by_proximity(&'a self, first: &str, second: &str, min: usize, max: usize, case_insensitive: bool) -> Option<(Match<'a>, Match<'a>)> {
+    self.find_words_by_proximity_bounds(first, second, min..=max, false, WordBounds::Both, case_insensitive)
+  }
+
+  /// Matches two whole words in either order with between min and max intervening words,
+  /// e.g. "disk" within 3 words of "error" is match_words_by_proximity("disk", "error", 0, 3, true)
+  fn match_words_by_proximity(&'a self, first: &str, second: &str, min: usize, max: usize, case_insensitive: bool) -> bool {
+    self.find_words_by_proximity(first, second, min, max, case_insensitive).is_some()
+  }
+
+  /// Matches two whole words in either order with between min and max intervening words in case-insensitive mode
+  fn match_words_by_proximity_ci(&'a self, first: &str, second: &str, min: usize, max: usize) -> bool {
+    self.match_words_by_proximity(first, second, min, max, true)
+  }
+
+  /// Matches two whole words in either order with between min and max intervening words in case-sensitive mode
+  fn match_words_by_proximity_cs(&'a self, first: &str, second: &str, min: usize, max: usize) -> bool {
+    self.match_words_by_proximity(first, second, min, max, false)
+  }
+
+  /// Finds the first sequence of whole words in the given order with no more than max_gap intervening words
+  /// between each word and the next, e.g. &["error", "disk", "full"] with a max_gap of 2 matches "error: the disk is full".
+  /// Returns the start and end byte offsets of the first sequence, or of the shortest if several sequences start at the same word.
+  /// Returns None if the words array is empty
+  fn find_words_in_order(&'a self, words: &[&str], max_gap: usize, case_insensitive: bool) -> Option<(usize, usize)> {
+    let mut word_matches: Vec<Vec<Match<'a>>> = Vec::with_capacity(words.len());
+    for word in words {
+      let matched_items = self.pattern_matches_outer(&build_whole_word_pattern(word), case_insensitive);
+      if matched_items.is_empty() {
+        return None;
+      }
+      word_matches.push(matched_items);
+    }
+    let (first_matches, next_word_matches) = word_matches.split_first()?;
+    let tokens = self.pattern_matches_vec(r#"\w+"#, false);
+    for first_match in first_matches {
+      let mut reachable: Vec<Match<'a>> = vec![*first_match];
+      for matched_items in next_word_matches {
+        reachable = matched_items.iter().filter(|next| {
+          reachable.iter().any(|previous| previous.end() <= next.start() && count_words_between(&tokens, previous.end(), next.start()) <= max_gap)
+        }).copied().collect();
+        if reachable.is_empty() {
+          break;
+        }
+      }
+      if let Some(end) = reachable.iter().map(|matched_item| matched_item.end()).min() {
+        return Some((first_match.start(), end));
+      }
+    }
+    None
+  }
+
+  /// Matches whole words in the given order with no more than max_gap intervening words between each word and the next
+  fn match_words_in_order(&'a self, words: &[&str], max_gap: usize, case_insensitive: bool) -> bool {
+    self.find_words_in_order(words, max_gap, case_insensitive).is_some()
+  }
+
+  /// Matches whole words in the given order with no more than max_gap intervening words in case-insensitive mode
+  fn match_words_in_order_ci(&'a self, words: &[&str], max_gap: usize) -> bool {
+    self.match_words_in_order(words, max_gap, true)
+  }
+
+  /// Matches whole words in the given order with no more than max_gap intervening words in case-sensitive mode
+  fn match_words_in_order_cs(&'a self, words: &[&str], max_gap: usize) -> bool {
+    self.match_words_in_order(words, max_gap, false)
+  }
+
+}
+
+/// Counts the words (\w+ matches sorted by position) lying wholly between the start and end byte offsets
+pub(crate) fn count_words_between(words: &[Match], start: usize, end: usize) -> usize {
+  let first_index = words.partition_point(|word| word.start() < start);
+  let last_index = words.partition_point(|word| word.end() <= end);
+  last_index.saturating_sub(first_index)
 }
 
 /// Automatic implementation for str/String as both implement PatternMatch and PatternCapture in this crate
@@ -187,6 +415,91 @@ pub trait ReplaceWord where Self:PatternReplace {
   /// Replace one or sets of whole words with case_insensitive flags as the last tuple element
   fn replace_word_sets(&self, pairs: &[(&str, &str, bool)]) -> Self where Self:Sized;
 
+  /// Replace words with bounds and a custom word boundary definition instead of \b, with a case_insensitive flag.
+  /// Characters before and after each word are kept and capture group references refer to groups in the word pattern.
+  /// Returns the string unchanged if the word pattern is not a valid regular expression
+  fn replace_word_boundary(&self, word: &str, replacement: &str, bounds: WordBounds, boundary: &WordBoundary, case_insensitive: bool) -> Self where Self:Sized + AsRef<str> + From<String> {
+    let text = self.as_ref();
+    Self::from(replace_bounded_words(text, word, replacement, bounds, boundary, case_insensitive).unwrap_or_else(|_| text.to_owned()))
+  }
+
+  /// Replace the parts of Unicode word tokens (UAX #29) matching a word pattern with bounds within each token
+  /// and a case_insensitive flag. Capture group references refer to groups in the word pattern.
+  /// Returns the string unchanged if the word pattern is not a valid regular expression
+  #[cfg(feature = "segmentation")]
+  fn replace_word_bounds_segmented(&self, word: &str, replacement: &str, bounds: WordBounds, case_insensitive: bool) -> Self where Self:Sized + AsRef<str> + From<String> {
+    let text = self.as_ref();
+    Self::from(replace_segmented_words(text, word, replacement, bounds, case_insensitive).unwrap_or_else(|_| text.to_owned()))
+  }
+
+  /// Replace whole Unicode word tokens with a boolean case_insensitive flag
+  #[cfg(feature = "segmentation")]
+  fn replace_word_segmented(&self, word: &str, replacement: &str, case_insensitive: bool) -> Self where Self:Sized + AsRef<str> + From<String> {
+    self.replace_word_bounds_segmented(word, replacement, WordBounds::Both, case_insensitive)
+  }
+
+  /// Replace whole Unicode word tokens in case-insensitive mode
+  #[cfg(feature = "segmentation")]
+  fn replace_word_segmented_ci(&self, word: &str, replacement: &str) -> Self where Self:Sized + AsRef<str> + From<String> {
+    self.replace_word_segmented(word, replacement, true)
+  }
+
+  /// Replace whole Unicode word tokens in case-sensitive mode
+  #[cfg(feature = "segmentation")]
+  fn replace_word_segmented_cs(&self, word: &str, replacement: &str) -> Self where Self:Sized + AsRef<str> + From<String> {
+    self.replace_word_segmented(word, replacement, false)
+  }
+
+  /// Replace literal words with boundary and case_insensitive options.
+  /// Regex meta characters in the word are escaped and $ in the replacement is inserted as is
+  fn replace_word_bounds_literal(&self, word: &str, replacement: &str, bounds: WordBounds, case_insensitive: bool) -> Self where Self:Sized {
+    let pattern = build_literal_word_pattern(word, bounds);
+    self.pattern_replace(&pattern, &escape_replacement(replacement), case_insensitive)
+  }
+
+  /// Replace literal whole words with case_insensitive options
+  fn replace_word_literal(&self, word: &str, replacement: &str, case_insensitive: bool) -> Self where Self:Sized {
+    self.replace_word_bounds_literal(word, replacement, WordBounds::Both, case_insensitive)
+  }
+
+  /// Replace words or phrases with boundary and case_insensitive options, where any run of whitespace
+  /// in the word matches any run of whitespace in the text, including line breaks and no-break spaces
+  fn replace_word_bounds_flex_space(&self, word: &str, replacement: &str, bounds: WordBounds, case_insensitive: bool) -> Self where Self:Sized {
+    let pattern = build_word_pattern(&build_flex_space_pattern(word), bounds);
+    self.pattern_replace(&pattern, replacement, case_insensitive)
+  }
+
+  /// Replace whole words or phrases with case_insensitive options, where any run of whitespace matches any run of whitespace
+  fn replace_word_flex_space(&self, word: &str, replacement: &str, case_insensitive: bool) -> Self where Self:Sized {
+    self.replace_word_bounds_flex_space(word, replacement, WordBounds::Both, case_insensitive)
+  }
+
+  /// Replace each English inflection of a whole word with the same inflection of the replacement,
+  /// e.g. mice with rats and ran with walked, with a case_insensitive flag.
+  /// A lower-case replacement takes the capitalisation of the matched word
+  fn replace_word_inflected(&self, word: &str, replacement: &str, case_insensitive: bool) -> Self where Self:Sized + AsRef<str> + From<String> {
+    let text = self.as_ref();
+    Self::from(replace_inflected_words(text, word, replacement, case_insensitive).unwrap_or_else(|_| text.to_owned()))
+  }
+
+  /// Replace inflections of one or pairs of whole words with a boolean case_insensitive flag
+  fn replace_words_inflected(&self, pairs: &[(&str, &str)], case_insensitive: bool) -> Self where Self:Sized + Clone + AsRef<str> + From<String> {
+    let mut output = self.clone();
+    for (word, replacement) in pairs {
+      output = output.replace_word_inflected(word, replacement, case_insensitive);
+    }
+    output
+  }
+
+  /// Replace one or pairs of literal whole words with a boolean case_insensitive flag
+  fn replace_words_literal(&self, pairs: &[(&str, &str)], case_insensitive: bool) -> Self where Self:Sized + Clone {
+    let mut output = self.clone();
+    for (word, replacement) in pairs {
+      output = output.replace_word_literal(word, replacement, case_insensitive);
+    }
+    output
+  }
+
 }
 
 
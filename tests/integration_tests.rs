@@ -187,6 +187,8 @@ fn test_match_count() {
   
 }
 
+// fancy-regex rejects quantified assertions such as \b?, see test_fancy_backend
+#[cfg(not(feature = "fancy"))]
 #[test]
 fn test_first_match() {
   let sample_text = r#"Trout belong mainly to two genera: Oncorhynchus and Salvelinus."#;
@@ -212,7 +214,131 @@ fn match_all_or_any_words() {
   let sample_text = "A species of teleost fish usually lives in only one kind of habitat at any stage of its life cycle.";
   let words = ["fish", "habitat", "trout"];
   assert_eq!(sample_text.match_words_ci(&words), false); // does not contain trout and should be false
-  assert!(sample_text.match_any_words_ci(&words)); 
+  assert!(sample_text.match_any_words_ci(&words));
+}
+
+#[test]
+fn test_word_list_patterns() {
+  // literal words are factored into a prefix trie
+  assert_eq!(build_word_list_pattern(&["cat", "car", "cart"], WordBounds::Both), r#"\b(?:ca(?:t|rt??))\b"#);
+  assert_eq!(build_word_list_pattern(&["1.5", "1"], WordBounds::Start), r#"\b(?:1(?:\.5)?)"#);
+  let sample_text = "The cart was full of 1.5 litre bottles";
+  assert!(sample_text.match_any_words_cs(&["car", "cart", "bicycle"]));
+  assert!(!sample_text.match_any_words_cs(&["car", "cat", "bicycle"]));
+  // words are escaped, while match_any_word_patterns accepts regular expressions
+  assert!(!sample_text.match_any_words_ci(&["bicycle", "bottles?", "cat"]));
+  assert!(sample_text.match_any_word_patterns(&["bicycle", "bottles?", "cat"], true));
+  assert!("Learn C++ today".match_any_words_cs(&["C++", "java"]));
+  let records = ["lions and tigers", "a cart horse", "cartography", "1x5 ratio", "1.5 ratio"];
+  assert_eq!(records.pattern_filter_any_words_ci(&["tiger", "tigers", "cart", "1.5"]), vec!["lions and tigers", "a cart horse", "1.5 ratio"]);
+  let words: Vec<String> = (0..2000).map(|n| format!("term{}", n)).collect();
+  let word_refs: Vec<&str> = words.iter().map(|word| word.as_str()).collect();
+  assert!("looking for TERM1999 here".match_any_words_ci(&word_refs));
+  assert!(!"looking for term20000 here".match_any_words_ci(&word_refs));
+  // earlier words keep priority as in a plain alternation
+  assert_eq!(build_word_list_pattern(&["cart", "car"], WordBounds::None), r#"(?:cart?)"#);
+  assert_eq!(build_word_list_pattern(&["cab", "ca", "cad"], WordBounds::None), r#"(?:ca(?:b||d))"#);
+  assert_eq!("carts".pattern_first_match(&build_word_list_pattern(&["car", "cart"], WordBounds::None), false).unwrap().as_str(), "car");
+  assert_eq!("carts".pattern_first_match(&build_word_list_pattern(&["cart", "car"], WordBounds::None), false).unwrap().as_str(), "cart");
+  assert_eq!("New York City".pattern_first_match(&build_word_list_pattern(&["new", "new york"], WordBounds::Both), true).unwrap().as_str(), "New");
+  // case-insensitive mode applies to patterns without word bounds at either end
+  assert!("CAT".pattern_match_ci(&build_word_list_pattern(&["Cat", "dog"], WordBounds::None)));
+  assert!("HOTDOG".pattern_match_ci(&build_word_list_pattern(&["Cat", "dog"], WordBounds::End)));
+}
+
+#[test]
+fn test_literal_mode() {
+  let sample_text = "Learn C++ for $5.00 (or c# for $5) today";
+  // regex syntax in user-supplied terms is matched literally
+  assert!(sample_text.pattern_match_literal("$5.00", false));
+  assert!(!sample_text.pattern_match_literal("$5.0.", false));
+  assert_eq!(sample_text.to_string().pattern_replace_literal("$5", "$10", false), "Learn C++ for $10.00 (or c# for $10) today");
+  assert_eq!(sample_text.to_string().pattern_replace_first_literal("(or", "[or", false), "Learn C++ for $5.00 [or c# for $5) today");
+  assert_eq!("a.b.c".pattern_split_literal(".", false), vec!["a", "b", "c"]);
+  assert_eq!("1 + 2 + 3".pattern_split_pair_literal(" + ", false), ("1".to_string(), "2 + 3".to_string()));
+  // word bounds only apply at word-character edges
+  assert!(sample_text.match_word_literal("c++", true));
+  assert!(!"Learn ObjC++".match_word_literal("c++", true));
+  assert!(sample_text.match_any_words_literal(&["java", "c#", ".net"], true));
+  assert!(sample_text.match_words_literal(&["c++", "c#", "$5"], true));
+  let languages = ["c++", "c#", "cpp"];
+  assert!("cpp or c#".match_any_words_literal(&languages, false));
+  assert!(!"cpp11 or objc#".match_any_words_literal(&languages, false));
+  let records = ["C++ and Rust", "C and Rust", "c++-like syntax"];
+  assert_eq!(records.pattern_filter_word_literal("c++", true), vec!["C++ and Rust", "c++-like syntax"]);
+  assert_eq!(records.pattern_filter_literal("c+", true), vec!["C++ and Rust", "c++-like syntax"]);
+  let replaced = sample_text.to_string().replace_words_literal(&[("c++", "Rust"), ("$5", "€5")], true);
+  assert_eq!(replaced, "Learn Rust for €5.00 (or c# for €5) today");
+  assert_eq!("C++ or c++".to_string().replace_word_literal("c++", "$0", false), "C++ or $0");
+  // case-insensitive mode also applies to word lists compiled into a leading group
+  assert!("Learn C++ today".match_any_words_literal(&["c++", "java"], true));
+  assert!("Built on .NET".match_any_words_literal(&[".net", "java"], true));
+  assert!(!"Built on .NET".match_any_words_literal(&[".net", "java"], false));
+  assert_eq!(["Learn C++ today", "Learn Rust", "JAVA or .Net"].pattern_filter_any_words_ci(&["c++", ".net", "Java"]), vec!["Learn C++ today", "JAVA or .Net"]);
+  assert!(build_regex(r#"(?:cat|dog)"#, true).unwrap().is_match("CAT"));
+  assert!(!build_regex(r#"(?-i)cat"#, true).unwrap().is_match("CAT"));
+}
+
+#[test]
+fn test_word_boundaries() {
+  let sample_text = "Send an e-mail or mail_merge, don't mail-bomb. Don't!";
+  // \b splits e-mail and don't
+  assert!(sample_text.match_word_ci("mail"));
+  assert!(sample_text.match_word_ci("don"));
+  let hyphenated = WordBoundary::new().with_hyphens().with_apostrophes();
+  assert!(sample_text.match_word_boundary("e-mail", WordBounds::Both, &hyphenated, false));
+  assert!(!sample_text.match_word_boundary("mail", WordBounds::Both, &hyphenated, false));
+  assert!(!sample_text.match_word_boundary("don", WordBounds::Both, &hyphenated, true));
+  assert_eq!(sample_text.count_word_boundary("don't", &hyphenated, true), 2);
+  // underscores as boundaries
+  let snake_case = WordBoundary::new().without_underscore();
+  assert!(sample_text.match_word_boundary("mail", WordBounds::Both, &snake_case, false));
+  assert_eq!(sample_text.count_word_boundary("mail", &snake_case, false), 3);
+  let matched_items = sample_text.word_matches_boundary("mail", WordBounds::Both, &snake_case, false);
+  assert_eq!(matched_items.iter().map(|m| m.start()).collect::<Vec<usize>>(), vec![10, 18, 36]);
+  // adjacent words share their separator
+  assert_eq!("cat cat,cat".count_word_boundary("cat", &WordBoundary::new(), false), 3);
+  // whitespace-only boundaries for tokens or text without \b boundaries
+  let spaces = WordBoundary::whitespace();
+  assert!("东京 大阪 京都".match_word_boundary("大阪", WordBounds::Both, &spaces, false));
+  assert!(!"东京大阪 京都".match_word_boundary("大阪", WordBounds::Both, &spaces, false));
+  assert_eq!("v1.2 or v1.2.3".count_word_boundary(r#"v1\.2"#, &spaces, false), 1);
+  // replacements keep the separators and expand groups in the word pattern
+  let replaced = sample_text.to_string().replace_word_boundary("(e)-mail", "${1}mail", WordBounds::Both, &hyphenated, false);
+  assert_eq!(replaced, "Send an email or mail_merge, don't mail-bomb. Don't!");
+  let replaced = sample_text.to_string().replace_word_boundary("don't", "do not", WordBounds::Both, &hyphenated, true);
+  assert_eq!(replaced, "Send an e-mail or mail_merge, do not mail-bomb. do not!");
+  let replaced = sample_text.to_string().replace_word_boundary("mail", "post", WordBounds::Start, &snake_case, false);
+  assert_eq!(replaced, "Send an e-post or post_merge, don't post-bomb. Don't!");
+  let records = ["mail-bomb", "e-mail", "mail"];
+  assert_eq!(records.pattern_filter_word_boundary("mail", &hyphenated, false), vec!["mail"]);
+}
+
+#[test]
+fn test_pattern_dialects() {
+  let files = ["server.log", "logs/server.log", "report_01.csv", "report_2024.csv", "photo.JPG", "notes.txt", "a/b/c/notes.txt"];
+  assert_eq!(files.pattern_filter_glob("*.log", false), vec!["server.log"]);
+  assert_eq!(files.pattern_filter_glob("**/*.log", false), vec!["server.log", "logs/server.log"]);
+  assert_eq!(files.pattern_filter_glob("report_??.csv", false), vec!["report_01.csv"]);
+  assert_eq!(files.pattern_filter_glob("*.{jpg,png,txt}", true), vec!["photo.JPG", "notes.txt"]);
+  assert_eq!(files.pattern_filter_glob("[!s]*", false), vec!["report_01.csv", "report_2024.csv", "photo.JPG", "notes.txt"]);
+  assert_eq!(files.pattern_filter_glob("report_[0-9][0-9].csv", false), vec!["report_01.csv"]);
+  assert!("a/b/c/notes.txt".pattern_match_glob("a/**/notes.txt", false));
+  assert!("[draft].md".pattern_match_glob("[[]draft].md", false));
+  assert!("{x".pattern_match_glob("{x", false));
+  assert_eq!(glob_to_regex("*.{jpg,png}"), r#"^(?s:[^/]*\.(?:jpg|png))$"#);
+  // SQL LIKE and ILIKE with the default and custom escape characters
+  let names = ["Anna Smith", "John Smithson", "Smith", "Goldsmith", "100% Smith"];
+  assert_eq!(names.pattern_filter_like("%Smith"), vec!["Anna Smith", "Smith", "100% Smith"]);
+  assert_eq!(names.pattern_filter_ilike("%smith%").len(), 5);
+  assert_eq!(names.pattern_filter_ilike("____ %"), vec!["Anna Smith", "John Smithson", "100% Smith"]);
+  assert_eq!(names.pattern_filter_like("%\\% %"), vec!["100% Smith"]);
+  assert_eq!(names.pattern_filter_dialect("%!%%", PatternDialect::Like(Some('!')), false), vec!["100% Smith"]);
+  assert!("Smith".pattern_match_ilike("sm_th"));
+  // simple wildcards and regular expressions
+  assert!("Annual Report 2024.pdf".pattern_match_dialect("*report ????.pdf", PatternDialect::Wildcard, true));
+  assert!(!"Annual Report 2024.pdf.bak".pattern_match_dialect("*report ????.pdf", PatternDialect::Wildcard, true));
+  assert!("Annual Report".pattern_match_dialect(r#"\bReport$"#, PatternDialect::Regex, false));
 }
 
 #[test]
@@ -278,6 +404,17 @@ fn test_build_regex() {
   let pattern_2 = r#"\bb[aeiou]llsh[aeiout\b"#;
   let regex_2 = build_regex(pattern_2, true);
   assert!(regex_2.is_err());
+
+  // the compiled regex keeps the replace and split methods of the backend
+  let re = build_regex(r#"(\d+)\s*"#, false).unwrap();
+  assert_eq!(re.replace_all("1 22  333", "<$1>"), "<1><22><333>");
+  assert_eq!(re.replace("1 22", "#"), "#22");
+  assert_eq!(re.replacen("1 22 333", 2, "#"), "##333");
+  assert_eq!(re.split("a1 b22c").collect::<Vec<&str>>(), vec!["a", "b", "c"]);
+  assert_eq!(re.splitn("a1b2c3d", 2).collect::<Vec<&str>>(), vec!["a", "b2c3d"]);
+  assert_eq!(re.shortest_match("ab12").map(|end| end >= 3), Some(true));
+  assert_eq!(re.find_at("1 22", 1).map(|matched_item| matched_item.as_str()), Some("22"));
+  assert_eq!(re.capture_names().count(), 2);
 }
 
 #[test]
@@ -371,3 +508,680 @@ fn test_pattern_filter() {
 
 }
 
+
+#[test]
+fn test_last_matches() {
+  let sample_str = "The cat sat on the mat next to the rat and the bat.";
+  let pattern = r#"\b(\w)at\b"#;
+  let last_match = sample_str.pattern_last_match(pattern, true).unwrap();
+  assert_eq!(last_match.as_str(), "bat");
+  // index methods should refer to the last match and not the first
+  assert_eq!(sample_str.pattern_last_start_index(pattern, true), Some(47));
+  assert_eq!(sample_str.pattern_last_index(pattern, true), Some(50));
+  assert_eq!(sample_str.pattern_first_index(pattern, true), Some(4));
+
+  // the last three outer matches in their original order
+  let last_matches = sample_str.pattern_last_matches(pattern, true, 3);
+  let last_strs: Vec<&str> = last_matches.iter().map(|m| m.as_str()).collect();
+  assert_eq!(last_strs, vec!["mat", "rat", "bat"]);
+  // requesting more matches than available yields all outer matches
+  assert_eq!(sample_str.pattern_last_matches(pattern, true, 10).len(), 5);
+
+  // last match ending before the word "next"
+  let next_index = sample_str.find("next").unwrap();
+  let before_match = sample_str.pattern_last_match_before(pattern, true, next_index).unwrap();
+  assert_eq!(before_match.as_str(), "mat");
+  assert!(sample_str.pattern_last_match_before(pattern, true, 5).is_none());
+
+  let (first, last) = sample_str.pattern_first_last_matches(pattern, true).unwrap();
+  assert_eq!((first.as_str(), last.as_str()), ("cat", "bat"));
+}
+
+#[test]
+fn test_match_positions() {
+  let sample_str = "Café au lait\nand crème brûlée 🍮 for dessert";
+  let pattern = r#"\b(cr[eè]me|dessert)\b"#;
+  let positions = sample_str.pattern_match_positions(pattern, true);
+  assert_eq!(positions.len(), 2);
+  let first = positions[0];
+  assert_eq!(first.as_str(), "crème");
+  assert_eq!(first.start.byte, 18);
+  assert_eq!(first.start.char_index, 17);
+  assert_eq!(first.start.utf16, 17);
+  assert_eq!((first.start.line, first.start.column), (1, 4));
+  assert_eq!(first.char_range(), (17, 22));
+  // the emoji occupies one char but two UTF-16 code units
+  let second = positions[1];
+  assert_eq!(second.start.char_index, 36);
+  assert_eq!(second.start.utf16, 37);
+  assert_eq!((second.start.line, second.start.column), (1, 23));
+  assert_eq!(sample_str.pattern_first_char_index(r#"dessert"#, false), Some(36));
+  assert_eq!(sample_str.pattern_first_utf16_index(r#"dessert"#, false), Some(37));
+  assert_eq!(sample_str.pattern_last_position(pattern, true).unwrap().as_str(), "dessert");
+
+  // convert back to byte offsets
+  assert_eq!(char_index_to_byte_index(sample_str, 36), Some(second.start.byte));
+  assert_eq!(utf16_index_to_byte_index(sample_str, 37), Some(second.start.byte));
+  assert_eq!(line_column_to_byte_index(sample_str, 1, 23), Some(second.start.byte));
+  // offsets within a surrogate pair or beyond the end of a line are not valid
+  assert_eq!(utf16_index_to_byte_index(sample_str, 31), None);
+  assert_eq!(line_column_to_byte_index(sample_str, 0, 20), None);
+  assert_eq!(byte_index_to_position(sample_str, second.start.byte), second.start);
+}
+
+#[test]
+fn test_overlapping_matches() {
+  let sample_str = "aaaa";
+  assert_eq!(sample_str.count_pattern("aa", false), 2);
+  assert_eq!(sample_str.count_pattern_overlapping("aa", false), 3);
+  let starts: Vec<usize> = sample_str.pattern_matches_overlapping("aa", false).iter().map(|m| m.start()).collect();
+  assert_eq!(starts, vec![0, 1, 2]);
+
+  // motif search in a DNA sequence with an inner group
+  let sequence = "GATATATGCATATAC";
+  let pattern = r#"AT(A)T"#;
+  assert_eq!(sequence.pattern_matches_outer(pattern, false).len(), 2);
+  let outer_matches = sequence.pattern_matches_overlapping(pattern, true);
+  let outer_starts: Vec<usize> = outer_matches.iter().map(|m| m.start()).collect();
+  assert_eq!(outer_starts, vec![1, 3, 9]);
+  // inner groups are included with the outer matches
+  assert_eq!(sequence.pattern_matches_overlapping_vec(pattern, false).len(), 6);
+
+  // word boundaries are evaluated against the whole haystack
+  assert_eq!("banana bandana".count_pattern_overlapping(r#"\bana"#, false), 0);
+  // multibyte characters are skipped as whole characters
+  assert_eq!("ééé".count_pattern_overlapping("éé", false), 2);
+}
+
+#[test]
+fn test_captures_in_collections() {
+  let log_lines = strs_to_owned(&[
+    "2024-03-01 ERROR disk /dev/sda1 full",
+    "2024-03-01 INFO backup started",
+    "2024-03-02 error timeout on /dev/sdb2, retrying /dev/sdb2",
+  ]);
+  let pattern = r#"/dev/(sd[a-z])\d"#;
+  let indexed_captures = log_lines.pattern_captures_indexed(pattern, false);
+  assert_eq!(indexed_captures.len(), 2);
+  let (index, captures) = &indexed_captures[1];
+  assert_eq!(*index, 2);
+  assert_eq!(captures.get(1).unwrap().as_str(), "sdb");
+
+  let indexed_matches = log_lines.pattern_matches_outer_indexed(pattern, false);
+  let summary: Vec<(usize, usize)> = indexed_matches.iter().map(|(index, matches)| (*index, matches.len())).collect();
+  assert_eq!(summary, vec![(0, 1), (2, 2)]);
+
+  // per-item and total counts
+  assert_eq!(log_lines.count_word_per_item("error", true), vec![1, 0, 1]);
+  assert_eq!(log_lines.count_word("error", true), 2);
+  assert_eq!(log_lines.count_pattern(r#"\d{4}-\d\d-\d\d"#, false), 3);
+
+  // also implemented for arrays of string slices
+  let phrases = ["cat and dog", "no pets", "dog, dog and cat"];
+  assert_eq!(phrases.count_pattern_per_item(r#"\bdog\b"#, false), vec![1, 0, 2]);
+  let word_matches = phrases.pattern_matches_vec_indexed(r#"\b(cat|dog)\b"#, false);
+  assert_eq!(word_matches.last().unwrap().1.len(), 3);
+  // an invalid regex yields empty results
+  assert!(phrases.pattern_captures_indexed(r#"(cat"#, false).is_empty());
+}
+
+#[test]
+fn test_lookaround_emulation() {
+  let sample_str = "Item A weighs 12kg, item B weighs 7 lb and item C costs -3kg credit";
+  // numbers followed by kg but not preceded by a minus sign
+  let conditions = [LookAround::FollowedBy(r#"\s*kg\b"#), LookAround::NotPrecededBy("-")];
+  let matches = sample_str.pattern_matches_around(r#"\d+"#, &conditions, true);
+  let matched_strs: Vec<&str> = matches.iter().map(|m| m.as_str()).collect();
+  assert_eq!(matched_strs, vec!["12"]);
+  assert!(sample_str.pattern_match_around(r#"\d+"#, &[LookAround::FollowedBy(r#"\s+lb"#)], true));
+  assert!(!sample_str.pattern_match_around(r#"\d+"#, &[LookAround::FollowedBy(r#"\s+oz"#)], true));
+
+  // words preceded by "item " in case-insensitive mode
+  let captures = sample_str.pattern_captures_around(r#"\b([A-Z])\b"#, &[LookAround::PrecededBy(r#"item\s"#)], true);
+  assert_eq!(captures.len(), 3);
+
+  // replacement with captured groups
+  let replaced = sample_str.pattern_replace_around(r#"(\d+)"#, &conditions, "${1}000g", false);
+  assert_eq!(replaced, "Item A weighs 12000gkg, item B weighs 7 lb and item C costs -3kg credit");
+
+  // split on commas not followed by a space
+  let parts = "a,b, c,d".pattern_split_around(",", &[LookAround::NotFollowedBy(r#"\s"#)], false);
+  assert_eq!(parts, vec!["a", "b, c", "d"]);
+
+  // invalid condition patterns return an error
+  assert!("abc".pattern_match_around_result("b", &[LookAround::FollowedBy("(c")], false).is_err());
+
+  // look-ahead conditions see the text before the end of the match, so \b is not a fake start of text
+  let matches = "foobar foo, bar".pattern_matches_around("foo", &[LookAround::FollowedBy(r#"\b"#)], false);
+  assert_eq!(matches.iter().map(|m| m.start()).collect::<Vec<usize>>(), vec![7]);
+  assert!(!"foobar".pattern_match_around("foo", &[LookAround::FollowedBy(r#"\Abar"#)], false));
+}
+
+#[test]
+fn test_repeated_words() {
+  let sample_str = "Paris in the the spring is lovely. The the end. This is is is odd";
+  let repeated = sample_str.find_repeated_words(false);
+  let repeated_strs: Vec<&str> = repeated.iter().map(|m| m.as_str()).collect();
+  assert_eq!(repeated_strs, vec!["the the", "is is", "is is"]);
+  assert_eq!(repeated[0].start(), 9);
+  // case-insensitive mode also catches "The the"
+  assert_eq!(sample_str.find_repeated_words(true).len(), 4);
+  // words must be whole and separated only by whitespace
+  assert!("then the theme, the. The".find_repeated_words(true).is_empty());
+}
+
+#[test]
+fn test_words_by_proximity() {
+  let sample_str = "The disk is now full and an error was logged, so the Error log needs a bigger disk";
+  // error is 3 words after full, but only in one direction
+  assert!(sample_str.match_words_by_proximity_ci("full", "error", 2, 3));
+  assert!(!sample_str.match_words_by_proximity_ci("full", "error", 0, 1));
+  assert!(sample_str.match_words_by_proximity("error", "full", 0, 2, true));
+  // the first satisfying pair is returned with the first word first
+  let (first, second) = sample_str.find_words_by_proximity("disk", "error", 0, 5, true).unwrap();
+  assert_eq!((first.start(), second.start()), (4, 28));
+  // case-sensitive whole words: "Error" is followed 2 words later by "needs", but "error" is not
+  assert!(sample_str.match_words_by_proximity_cs("Error", "needs", 1, 1));
+  assert!(!sample_str.match_words_by_proximity_cs("error", "needs", 0, 5));
+  // order-sensitive with word start bounds
+  assert!(sample_str.match_words_by_proximity_bounds("log", "bigg", 0..=3, true, WordBounds::Start, true));
+  assert!(!sample_str.match_words_by_proximity_bounds("bigg", "log", 0..=3, true, WordBounds::Start, true));
+  assert!(sample_str.match_words_by_proximity_bounds("bigg", "log", 0..=3, false, WordBounds::Start, true));
+  // capture groups within word patterns are not word hits of their own
+  let (first, second) = "red apple".find_words_by_proximity("red", "(a)pple", 0, 2, true).unwrap();
+  assert_eq!((first.as_str(), second.as_str(), second.range()), ("red", "apple", 4..9));
+}
+
+#[test]
+fn test_words_in_order() {
+  let sample_str = "Error: the disk is now full. Disk error logged, disk full";
+  assert!(sample_str.match_words_in_order_ci(&["error", "disk", "full"], 2));
+  assert!(!sample_str.match_words_in_order_ci(&["error", "disk", "full"], 0));
+  assert_eq!(sample_str.find_words_in_order(&["error", "disk", "full"], 2, true), Some((0, 27)));
+  // only the second sequence has no more than one word in each gap
+  assert_eq!(sample_str.find_words_in_order(&["error", "disk", "full"], 1, true), Some((34, 57)));
+  assert_eq!(sample_str.find_words_in_order(&["disk", "error", "disk"], 2, true), Some((29, 52)));
+  assert!(!sample_str.match_words_in_order_cs(&["Disk", "Error"], 3));
+  // the first sequence that satisfies the gap is not always built from the nearest next word
+  assert!("a b b x c".match_words_in_order_cs(&["a", "b", "c"], 1));
+  assert_eq!(sample_str.find_words_in_order(&[], 2, true), None);
+  // capture groups within word patterns are not word hits of their own
+  assert_eq!("error full".find_words_in_order(&["error", "(f)ull"], 0, true), Some((0, 10)));
+}
+
+#[test]
+fn test_search_queries() {
+  let sample_str = "The cat chased a mouse past the sleeping dog";
+  assert!(sample_str.match_query_ci(r#"cat AND (dog OR mouse) -fish"#));
+  assert!(!sample_str.match_query_ci(r#"cat AND (dog OR mouse) -dog"#));
+  // implicit AND, quoted phrases, wildcards and whole words only
+  assert!(sample_str.match_query_ci(r#""chased a mouse" sleep*"#));
+  assert!(!sample_str.match_query_ci(r#""chased the mouse""#));
+  assert!(!sample_str.match_query_ci("chase"));
+  assert!(sample_str.match_query_ci("ch?sed NOT (horse OR fish)"));
+  assert!("Which colour is it?".match_query_ci("colo*r"));
+  assert!("Learn C++ and .NET".match_query_ci("c++ .net"));
+  // lower-case operators are ordinary words and terms are case-sensitive in _cs mode
+  assert!(!sample_str.match_query_ci("cat or horse"));
+  assert!(!sample_str.match_query_cs("Cat OR Mouse"));
+
+  // parse errors with byte positions
+  assert_eq!(sample_str.match_query_result(r#"cat AND "dog"#, true), Err(QueryError::UnterminatedPhrase(8)));
+  assert_eq!(sample_str.match_query_result("(cat OR dog", true), Err(QueryError::UnclosedParenthesis(0)));
+  assert_eq!(sample_str.match_query_result("cat AND", true), Err(QueryError::MissingOperand(4)));
+  assert_eq!(sample_str.match_query_result("cat) dog", true), Err(QueryError::UnexpectedToken(3, ")".to_owned())));
+  assert_eq!(sample_str.match_query_result("  ", true), Err(QueryError::EmptyQuery));
+  // a minus sign without a directly following term is not a search term
+  assert_eq!(sample_str.match_query_result("-", true), Err(QueryError::MissingOperand(0)));
+  assert_eq!(sample_str.match_query_result("cat - dog", true), Err(QueryError::MissingOperand(4)));
+  assert_eq!(sample_str.match_query_result("(cat -)", true), Err(QueryError::MissingOperand(5)));
+  assert_eq!(QueryError::MissingOperand(4).to_string(), "missing search term after operator at 4");
+
+  // filter collections with a query compiled once
+  let strings = strs_to_owned(&["A grey cat", "A grey dog and a cat", "A black fish"]);
+  assert_eq!(strings.filter_query_ci("cat -dog OR fish"), strs_to_owned(&["A grey cat", "A black fish"]));
+  let strs = ["A grey cat", "A grey dog and a cat", "A black fish"];
+  assert_eq!(strs.filter_query_ci(r#""grey dog" OR black"#), vec!["A grey dog and a cat", "A black fish"]);
+  assert!(strs.filter_query_result("cat OR", true).is_err());
+  let search_query = SearchQuery::new("grey -dog", true).unwrap();
+  assert_eq!(search_query.positive_terms().len(), 1);
+  assert_eq!(search_query.negative_terms()[0].words(), &["dog".to_owned()]);
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Article {
+  title: String,
+  body: String,
+}
+
+impl WeightedFields for Article {
+  fn weighted_fields(&self) -> Vec<(&str, f64)> {
+    vec![(&self.title, 3.0), (&self.body, 1.0)]
+  }
+}
+
+#[test]
+fn test_relevance_ranking() {
+  let records = strs_to_owned(&[
+    "Disk space warnings",
+    "The disk is full: disk error 28",
+    "Unrelated log entry",
+    "Error: the disk is almost full",
+    "Diskette drives and errors",
+  ]);
+  let ranked = records.rank_by_words_ci(&["disk", "error"]);
+  assert_eq!(ranked.iter().map(|scored| scored.index).collect::<Vec<usize>>(), vec![1, 3, 0, 4]);
+  // two whole-word hits for disk, one for error and a proximity bonus for adjacent words
+  assert_eq!(ranked[0].score, 3.0 + 1.0);
+  // whole words and adjacent terms outscore partial hits
+  assert_eq!(ranked[1].score, 2.0 + 5.0 / 6.0);
+  assert_eq!(ranked[3].score, 1.0);
+  assert_eq!(ranked[3].matched_terms, vec!["disk", "error"]);
+  assert_eq!(ranked[2].matched_terms, vec!["disk"]);
+
+  // weighted terms with custom weights and an invalid pattern
+  let weights = ScoreWeights { partial: 0.0, proximity: 0.0, ..Default::default() };
+  let ranked = records.rank_by_terms(&[("disk", 1.0), ("errors?", 4.0)], &weights, true);
+  assert_eq!(ranked.iter().map(|scored| scored.index).collect::<Vec<usize>>(), vec![1, 3, 4, 0]);
+  assert!(records.rank_by_terms_result(&[("disk(", 1.0)], &weights, true).is_err());
+
+  // custom structs with field weights
+  let articles = [
+    Article { title: "Fixing errors".to_owned(), body: "A full disk causes many errors".to_owned() },
+    Article { title: "Disk maintenance".to_owned(), body: "Check the disk regularly".to_owned() },
+  ];
+  let ranked = articles.rank_by_words_ci(&["disk"]);
+  assert_eq!(ranked[0].item.title, "Disk maintenance");
+  assert_eq!(ranked[0].score, 4.0);
+  assert_eq!(ranked[1].score, 1.0);
+}
+
+#[test]
+fn test_word_index() {
+  let records = strs_to_owned(&[
+    "The disk is full",
+    "Disk error on drive C",
+    "Hip-hop and pop music",
+    "Diskette drives are obsolete",
+    "A full-text search engine",
+    "Colour and color",
+  ]);
+  let index = WordIndex::new(&records);
+  assert_eq!(index.len(), 6);
+  assert_eq!(index.postings("DISK"), &[0, 1]);
+  // whole words, word starts and ends with the same results as pattern_filter_word
+  assert_eq!(index.find_word("disk", true), vec![0, 1]);
+  assert_eq!(index.filter_word("disk", true), records.pattern_filter_word_ci("disk"));
+  assert_eq!(index.find_word("Disk", false), vec![1]);
+  assert_eq!(index.find_word_start("disk", true), vec![0, 1, 3]);
+  assert_eq!(index.find_word_end("ives", true), vec![3]);
+  assert_eq!(index.find_word_bounds("isk", WordBounds::None, true), vec![0, 1, 3]);
+  // words with punctuation and wildcards are verified against candidate records
+  assert_eq!(index.find_word("hip-hop", true), vec![2]);
+  assert_eq!(index.find_word("full-text", true), vec![4]);
+  assert_eq!(index.find_word("full", true), vec![0, 4]);
+  assert_eq!(index.find_word("colo*r", true), vec![5]);
+  assert_eq!(index.find_words(&["disk", "error"], true), vec![1]);
+  assert_eq!(index.find_any_words(&["pop", "search"], true), vec![2, 4]);
+  // boolean queries
+  assert_eq!(index.search_result("disk* -error", true), Ok(vec![0, 3]));
+  assert_eq!(index.search_result(r#""disk error" OR (full NOT disk)"#, true), Ok(vec![1, 4]));
+  assert_eq!(index.filter_query("Colour OR hip OR Pop", false), vec!["Colour and color"]);
+  assert!(index.search_result("disk AND", true).is_err());
+  let search_query = SearchQuery::new("drive*", true).unwrap();
+  assert_eq!(index.search(&search_query), records.iter().enumerate().filter(|(_, record)| search_query.is_match(record)).map(|(id, _)| id).collect::<Vec<usize>>());
+}
+
+#[test]
+fn test_prefiltered_collections() {
+  // collection methods may reject strings lacking required literals before running the regex,
+  // so results must be the same as matching each string on its own
+  let records = strs_to_owned(&[
+    "ERROR 28 request failed after timeout",
+    "error 28: Timeout",
+    "INFO request completed",
+    "Disk quota exceeded",
+    "The colour and the color",
+    "abab ab",
+    "STRASSE and Straße",
+    "\u{212A}elvin scale",
+  ]);
+  let patterns = [
+    (r#"ERROR.*timeout"#, false),
+    (r#"error \d+.*timeout"#, true),
+    (r#"(?:quota|timeout) exceeded"#, true),
+    (r#"\bcolou?r\b"#, false),
+    (r#"(ab){2}"#, false),
+    (r#"\bERROR\b.*time(out)?"#, false),
+    (r#"(?i)ERROR 28|info"#, false),
+    (r#"kelvin|strasse"#, true),
+    (r#"[a-z]+ \d+"#, true),
+  ];
+  let strs: Vec<&str> = records.iter().map(|record| record.as_str()).collect();
+  for (pattern, case_insensitive) in patterns {
+    let expected: Vec<bool> = records.iter().map(|record| record.pattern_match(pattern, case_insensitive)).collect();
+    assert_eq!(records.pattern_matches(pattern, case_insensitive), expected, "{}", pattern);
+    assert_eq!(strs.pattern_matches(pattern, case_insensitive), expected, "{}", pattern);
+    assert_eq!(records.pattern_match(pattern, case_insensitive), expected.contains(&true));
+    let filtered: Vec<&str> = strs.iter().zip(&expected).filter(|(_, is_matched)| **is_matched).map(|(record, _)| *record).collect();
+    assert_eq!(strs.pattern_filter(pattern, case_insensitive), filtered, "{}", pattern);
+  }
+  // Unicode case folding matches the Kelvin sign with k
+  #[cfg(any(not(feature = "lite"), feature = "fancy"))]
+  assert!(records.pattern_matches_ci("kelvin")[7]);
+}
+
+#[cfg(feature = "dictionary")]
+#[test]
+fn test_dictionary_replacement() {
+  let pairs = [("new", "old"), ("new york", "NYC"), ("c++", "C Plus Plus"), ("cat", "dog")];
+  let dictionary = WordDictionary::whole_words(&pairs, true).unwrap();
+  assert_eq!(dictionary.len(), 4);
+  // leftmost-longest replacement in one pass, so replacements are never replaced again
+  let sample = "New York cats love new things and C++, not a new yorker cat".to_string();
+  let expected = "NYC cats love old things and C Plus Plus, not a old yorker dog".to_string();
+  assert_eq!(sample.replace_with_dictionary(&dictionary), expected);
+  // case-sensitive whole words
+  assert_eq!(sample.replace_dictionary_words_cs(&pairs), "New York cats love old things and C++, not a old yorker dog");
+  // start bounds only match word prefixes
+  let prefixes = WordDictionary::new(&[("cat", "dog")], WordBounds::Start, false).unwrap();
+  assert_eq!("cats and bobcats".to_string().replace_with_dictionary(&prefixes), "dogs and bobcats");
+  let matches = dictionary.find_matches("a cat in New York");
+  assert_eq!(matches.len(), 2);
+  assert_eq!((matches[1].start, matches[1].end, dictionary.replacement(matches[1].index)), (9, 17, Some("NYC")));
+  // vectors of strings
+  let lines = strs_to_owned(&["the cat sat", "no match here"]);
+  assert_eq!(lines.replace_with_dictionary(&dictionary), strs_to_owned(&["the dog sat", "no match here"]));
+  // letters beyond ASCII match in lower case, upper case or capitalised in case-insensitive mode
+  let accented = WordDictionary::whole_words(&[("émile", "Emil"), ("smørbrød", "sandwich")], true).unwrap();
+  assert_eq!(accented.replace("ÉMILE, Émile and éMile eat SMØRBRØD"), "Emil, Emil and Emil eat sandwich");
+  assert_eq!(accented.replace("smØrbrød"), "smØrbrød");
+  let accented_cs = WordDictionary::whole_words(&[("émile", "Emil")], false).unwrap();
+  assert_eq!(accented_cs.replace("Émile and émile"), "Émile and Emil");
+}
+
+#[cfg(feature = "backreferences")]
+#[test]
+fn test_backreferences() {
+  let sample_str = "Paris in the the spring";
+  assert!(sample_str.pattern_match_backref(r#"\b(\w+)\s+\1\b"#, true));
+  assert!(!"Paris in the spring".pattern_match_backref(r#"\b(\w+)\s+\1\b"#, true));
+  let replaced = sample_str.pattern_replace_backref(r#"\b(\w+)\s+\1\b"#, "$1", true);
+  assert_eq!(replaced, "Paris in the spring");
+
+  // matching open and close tags
+  let html = "<b>bold</b> <i>italic</b> <em>emphasis</em>";
+  let tags = html.pattern_captures_backref(r#"<(\w+)>[^<]*</\1>"#, false);
+  let tag_names: Vec<&str> = tags.iter().map(|c| c.get(1).unwrap().as_str()).collect();
+  assert_eq!(tag_names, vec!["b", "em"]);
+  assert!(matches!(sample_str.pattern_match_backref_result(r#"(\w+\s+\1"#, false), Err(Error::Syntax(..))));
+  let matched_items = sample_str.pattern_matches_backref(r#"\b(\w+)\s+\1\b"#, true);
+  assert_eq!(matched_items.iter().map(|matched_item| matched_item.range()).collect::<Vec<_>>(), vec![9..16]);
+}
+
+#[cfg(feature = "fancy")]
+#[test]
+fn test_fancy_backend() {
+  // native look-around assertions with the standard trait methods
+  let sample_str = "The items weigh 12kg, 100px and -5kg";
+  let weights = sample_str.pattern_matches_vec(r#"(?<!-)\b\d+(?=kg)"#, true);
+  assert_eq!(weights.iter().map(|m| m.as_str()).collect::<Vec<&str>>(), vec!["12"]);
+  assert_eq!(sample_str.to_string().pattern_replace(r#"\d+(?=px)"#, "200", true), "The items weigh 12kg, 200px and -5kg");
+  // back-references and atomic groups
+  assert!("Paris in the the spring".pattern_match_ci(r#"\b(\w+)\s+\1\b"#));
+  assert!(!"aaab".pattern_match_cs(r#"^(?>a+)ab$"#));
+  let parts = "a1b22c".to_string().pattern_split_cs(r#"(?<=\d)(?=[a-z])"#);
+  assert_eq!(parts, vec!["a1", "b22", "c"]);
+  // fancy takes precedence over lite and regex when several backends are enabled
+  assert_eq!(backend_name(), "fancy-regex");
+  // errors are mapped to the crate's own error type
+  assert!(matches!("abc".pattern_match_result(r#"(\w+"#, true), Err(Error::Syntax(..))));
+  // quantified assertions such as \b? are rejected
+  let sample_text = r#"Trout belong mainly to two genera: Oncorhynchus and Salvelinus."#;
+  assert!(sample_text.pattern_first_match(r#"\bonco\w+us\b?"#, true).is_none());
+  assert_eq!(sample_text.pattern_first_match(r#"\bonco\w+us\b"#, true).unwrap().as_str(), "Oncorhynchus");
+  let strings = strs_to_owned(&["repeat repeat", "once"]);
+  assert_eq!(strings.pattern_matches(r#"(\w+) \1"#, true), vec![true, false]);
+}
+
+#[cfg(all(feature = "lite", not(feature = "fancy")))]
+#[test]
+fn test_lite_backend() {
+  // ASCII patterns work the same with all traits
+  let sample_string = "Trout, salmon and char are fish".to_string();
+  assert!(sample_string.pattern_match_ci(r#"\bSALMON\b"#));
+  assert_eq!(sample_string.pattern_replace_ci(r#"\bchar\b"#, "pike"), "Trout, salmon and pike are fish");
+  assert_eq!(sample_string.pattern_split_cs(r#",?\s+and\s+|,\s*"#), vec!["Trout", "salmon", "char are fish"]);
+  assert_eq!(sample_string.pattern_matches_vec(r#"\b\w+\b"#, true).len(), 6);
+  assert!(sample_string.match_word_ci("trout"));
+  let strings = strs_to_owned(&["perch", "Pike", "carp"]);
+  assert_eq!(strings.pattern_filter_ci(r#"^p"#), strs_to_owned(&["perch", "Pike"]));
+  // \w and \b are ASCII only, so accented letters are not word characters
+  let accented_str = "Un café très crème";
+  assert!(!accented_str.pattern_match_ci(r#"\bcafé\b"#));
+  assert_eq!(accented_str.pattern_matches_vec(r#"\w+"#, true).iter().map(|m| m.as_str()).collect::<Vec<&str>>(), vec!["Un", "caf", "tr", "s", "cr", "me"]);
+  // case-insensitive matching only folds ASCII letters
+  assert!(accented_str.pattern_match_ci("CAFé"));
+  assert!(!accented_str.pattern_match_ci("CAFÉ"));
+  // Unicode classes are not supported and fail to compile
+  assert!(accented_str.pattern_match_result(r#"\p{L}+"#, true).is_err());
+  // lite is selected whenever fancy is not enabled and errors are mapped to the crate's own error type
+  assert_eq!(backend_name(), "regex-lite");
+  assert!(matches!(accented_str.pattern_match_result(r#"\p{L}+"#, true), Err(Error::Syntax(..))));
+}
+
+#[cfg(feature = "segmentation")]
+#[test]
+fn test_segmented_words() {
+  let sample_text = "The café's 🍰 costs 3.50, I can't resist! 東京タワー";
+  let words: Vec<&str> = sample_text.segmented_words().map(|(_, word)| word).collect();
+  assert_eq!(words, vec!["The", "café's", "costs", "3.50", "I", "can't", "resist", "東", "京", "タワー"]);
+  assert_eq!(segment_words("I can't").collect::<Vec<(usize, &str)>>(), vec![(0, "I"), (2, "can't")]);
+  // contractions, decimals and katakana runs are single tokens, unlike with \b
+  assert!(sample_text.match_word_ci("can"));
+  assert!(!sample_text.match_word_segmented_ci("can"));
+  assert!(sample_text.match_word_segmented_ci("CAN'T"));
+  assert!(sample_text.match_word_segmented_cs("タワー"));
+  assert!(!sample_text.match_word_segmented_cs("タワ"));
+  assert!(sample_text.match_word_bounds_segmented("タワ", WordBounds::Start, false));
+  let decimals = sample_text.word_matches_segmented(r#"\d+\.\d+"#, WordBounds::Both, false);
+  assert_eq!(decimals.iter().map(|m| (m.start(), m.as_str())).collect::<Vec<(usize, &str)>>(), vec![(23, "3.50")]);
+  let prefixes = sample_text.word_matches_segmented("caf", WordBounds::Start, true);
+  assert_eq!(prefixes.iter().map(|m| (m.start(), m.as_str())).collect::<Vec<(usize, &str)>>(), vec![(4, "caf")]);
+  assert_eq!("Run, run, RUNNING".count_word_segmented("run", true), 2);
+  // replacements keep the surrounding text and expand groups in the word pattern
+  let replaced = sample_text.to_string().replace_word_segmented_ci("can't", "cannot");
+  assert_eq!(replaced, "The café's 🍰 costs 3.50, I cannot resist! 東京タワー");
+  let replaced = "3.50 or 4.75".to_string().replace_word_segmented(r#"(\d+)\.(\d+)"#, "$1,$2", false);
+  assert_eq!(replaced, "3,50 or 4,75");
+  assert_eq!("東京 is big".to_string().replace_word_segmented_cs("東", "Higashi"), "Higashi京 is big");
+  let records = ["don't stop", "do it", "dont"];
+  assert_eq!(records.pattern_filter_word_segmented_ci("do"), vec!["do it"]);
+  assert_eq!(records.pattern_filter_word_bounds_segmented("don", WordBounds::Start, true), vec!["don't stop", "dont"]);
+  let owned_records = strs_to_owned(&records);
+  assert_eq!(owned_records.pattern_filter_word_segmented_cs("dont"), strs_to_owned(&["dont"]));
+}
+
+#[cfg(feature = "folding")]
+#[test]
+fn test_accent_folding() {
+  assert_eq!(fold_accents("Crème Brûlée, Æsir, Straße, Øresund"), "Creme Brulee, AEsir, Strasse, Oresund");
+  let sample_text = "Un café à Zürich, Straße 5";
+  assert!(sample_text.match_word_folded_ci("CAFE"));
+  assert!(sample_text.match_word_folded("Zurich", false));
+  assert!(!sample_text.match_word_folded("zurich", false));
+  // the pattern is folded too
+  assert!("Un cafe".match_word_folded_ci("café"));
+  // offsets and slices refer to the unfolded text
+  let matched_item = sample_text.pattern_first_match_folded("cafe", true).unwrap();
+  assert_eq!((matched_item.start(), matched_item.end(), matched_item.as_str()), (3, 8, "café"));
+  let matched_item = sample_text.pattern_first_match_folded("strasse", true).unwrap();
+  assert_eq!(matched_item.as_str(), "Straße");
+  // a match ending within an expanded ligature covers the whole original character
+  let matched_item = sample_text.pattern_first_match_folded("stras", true).unwrap();
+  assert_eq!(matched_item.as_str(), "Straß");
+  // combining marks are removed as well as precomposed accents
+  let decomposed_text = "Cafe\u{301} noir";
+  let matched_item = decomposed_text.pattern_first_match_folded(r#"\bcafe\b"#, true).unwrap();
+  assert_eq!(matched_item.as_str(), "Cafe\u{301}");
+  assert_eq!("Noël, noel, NOËL".count_word_folded("noel", true), 3);
+  assert!(!sample_text.pattern_match_folded_ci("caf(e"));
+  let records = ["Señor Nuñez", "Senora Diaz", "Muñoz"];
+  assert_eq!(records.pattern_filter_folded_ci("n[uo]n"), vec!["Señor Nuñez"]);
+  assert_eq!(records.pattern_filter_word_folded("senor", true), vec!["Señor Nuñez"]);
+  let strings = strs_to_owned(&["Ångström", "angle"]);
+  assert_eq!(strings.pattern_filter_folded("^Ang", false), strs_to_owned(&["Ångström"]));
+  // only literals are folded: classes keep characters without a single-character folded form
+  assert!("cafe".pattern_match_folded("^caf[é]$", false));
+  assert!(!"s".pattern_match_folded("^[ß]$", false));
+  // a quantifier applies to the whole folded form of ß
+  assert!("Strassss".pattern_match_folded("^straß+$", true));
+  assert!(!"Strasss".pattern_match_folded("^straß+$", true));
+  // capture groups refer to the unfolded text
+  let captures = sample_text.pattern_captures_folded(r#"(\w+) a (zurich)"#, true);
+  assert_eq!(captures.len(), 1);
+  assert_eq!(captures[0].get(1).map(|group| group.as_str()), Some("café"));
+  assert_eq!(captures[0].get(2).map(|group| (group.start(), group.as_str())), Some((12, "Zürich")));
+  assert!(sample_text.pattern_captures_folded_result("caf(e", true).is_err());
+}
+
+#[cfg(feature = "normalization")]
+#[test]
+fn test_normalized_matching() {
+  let nfd_text = "Cafe\u{301} cre\u{300}me, cafe\u{301}";
+  let nfc_pattern = "café";
+  // a precomposed pattern does not match decomposed text without normalization
+  assert!(!nfd_text.pattern_match_ci(nfc_pattern));
+  assert!(nfd_text.pattern_match_normalized_ci(nfc_pattern, NormalizationForm::Nfc));
+  assert!(nfd_text.pattern_match_normalized_ci(nfc_pattern, NormalizationForm::Nfd));
+  assert!(!nfd_text.pattern_match_normalized_cs("CAFÉ", NormalizationForm::Nfc));
+  assert_eq!(nfd_text.count_pattern_normalized(nfc_pattern, NormalizationForm::Nfc, true), 2);
+  // offsets and slices refer to the original text, including trailing combining marks
+  let matched_items = nfd_text.pattern_matches_normalized("caf", NormalizationForm::Nfc, true);
+  assert_eq!(matched_items.iter().map(|m| (m.start(), m.end())).collect::<Vec<(usize, usize)>>(), vec![(0, 3), (16, 19)]);
+  let matched_item = nfd_text.pattern_first_match_normalized("crè", NormalizationForm::Nfc, false).unwrap();
+  assert_eq!((matched_item.start(), matched_item.as_str()), (7, "cre\u{300}"));
+  // a match of a base letter covers the whole decomposed character
+  let matched_item = nfd_text.pattern_first_match_normalized("cafe", NormalizationForm::Nfd, true).unwrap();
+  assert_eq!(matched_item.as_str(), "Cafe\u{301}");
+  // compatibility forms fold ligatures and fullwidth letters
+  assert!("Ｒｕｓｔ ﬁles".match_word_normalized("rust", NormalizationForm::Nfkc, true));
+  assert!("Ｒｕｓｔ ﬁles".pattern_match_normalized("files", NormalizationForm::Nfkd, false));
+  assert!(!"Ｒｕｓｔ ﬁles".pattern_match_normalized("files", NormalizationForm::Nfc, false));
+  // Hangul jamo compose across runs
+  let jamo_text = "\u{1112}\u{1161}\u{11AB}\u{1100}\u{116E}\u{11A8}";
+  let matched_item = jamo_text.pattern_first_match_normalized("국", NormalizationForm::Nfc, false).unwrap();
+  assert_eq!((matched_item.start(), matched_item.end()), (9, 18));
+  // replacements only change the matched characters and keep the rest of the text as it was
+  let replaced = nfd_text.to_string().pattern_replace_normalized_ci(r#"caf(é)"#, "Kaff$1", NormalizationForm::Nfc);
+  assert_eq!(replaced, "Kaffé cre\u{300}me, Kaffé");
+  let texts = vec!["nai\u{308}ve".to_string(), "naïve".to_string()];
+  assert_eq!(texts.pattern_replace_normalized_cs("naïve", "naive", NormalizationForm::Nfc), strs_to_owned(&["naive", "naive"]));
+  assert_eq!(nfd_text.to_string().pattern_replace_normalized_ci("caf(", "x", NormalizationForm::Nfc), nfd_text);
+  assert_eq!(NormalizationForm::Nfc.normalize(nfd_text), "Café crème, café");
+  // only literals are normalized, so classes and compatibility characters never turn into other syntax
+  assert!(!"cafe".pattern_match_normalized("caf[é]$", NormalizationForm::Nfd, false));
+  assert!("café".pattern_match_normalized("caf[é]$", NormalizationForm::Nfc, false));
+  assert!("a*b".pattern_match_normalized("^a＊b$", NormalizationForm::Nfkc, false));
+  assert!(!"aab".pattern_match_normalized("^a＊b$", NormalizationForm::Nfkc, false));
+  // a quantifier applies to a decomposed character with its combining marks
+  assert!("e\u{301}e\u{301}".pattern_match_normalized("^é+$", NormalizationForm::Nfd, false));
+  // capture groups refer to the original text
+  let captures = nfd_text.pattern_captures_normalized(r#"(\w+) (crè)me"#, NormalizationForm::Nfc, true);
+  assert_eq!(captures.len(), 1);
+  assert_eq!(captures[0].get(1).map(|group| group.as_str()), Some("Cafe\u{301}"));
+  assert_eq!(captures[0].get(2).map(|group| (group.start(), group.as_str())), Some((7, "cre\u{300}")));
+}
+
+#[cfg(feature = "stemming")]
+#[test]
+fn test_word_stems() {
+  let sample_text = "She runs daily, was Running late and parked by the runway.";
+  assert!(!sample_text.match_word_ci("run"));
+  assert!(sample_text.match_word_stem("run", StemLanguage::English));
+  // surface forms are reported with byte offsets, but runway has a different stem
+  assert_eq!(sample_text.find_word_stems("running", StemLanguage::English), vec![(4, "runs"), (20, "Running")]);
+  assert_eq!(sample_text.count_word_stem("runs", StemLanguage::English), 2);
+  assert_eq!(stem_word("Running", StemLanguage::English), "run");
+  // phrases match consecutive words with the same stems
+  assert_eq!(sample_text.find_word_stems("park by", StemLanguage::English), vec![(37, "parked by")]);
+  assert!(!sample_text.match_word_stem("park the", StemLanguage::English));
+  assert!(sample_text.match_word_stems(&["run", "parking"], StemLanguage::English));
+  assert!(!sample_text.match_any_word_stems(&["walk", "car"], StemLanguage::English));
+  assert!(!sample_text.match_word_stem("", StemLanguage::English));
+  let records = ["Connected devices", "A connection", "Connecticut"];
+  assert_eq!(records.pattern_filter_word_stem("connecting", StemLanguage::English), vec!["Connected devices", "A connection"]);
+  let german_records = strs_to_owned(&["Die Häuser", "Ein Haus", "Hausaufgaben"]);
+  assert_eq!(german_records.pattern_filter_word_stem("häusern", StemLanguage::German), strs_to_owned(&["Die Häuser", "Ein Haus"]));
+  // apostrophes within words are kept, so contractions do not match their first part
+  assert!(!"I can't".match_word_stem("can", StemLanguage::English));
+  assert_eq!("I can't, she can’t".find_word_stems("can't", StemLanguage::English), vec![(2, "can't")]);
+  assert_eq!("the 'runner' runs".find_word_stems("run", StemLanguage::English), vec![(13, "runs")]);
+}
+
+#[test]
+fn test_flex_space_matching() {
+  let sample_text = "Terms of\nservice apply to the  data\u{a0}controller\tonly.";
+  assert!(!sample_text.match_word_ci("terms of service"));
+  assert!(sample_text.match_word_flex_space("terms of service", true));
+  assert!(sample_text.pattern_match_flex_space(r#"data controller\b"#, false));
+  assert!(!sample_text.pattern_match_flex_space("datacontroller", false));
+  assert!(sample_text.match_word_bounds_flex_space("the data contr", WordBounds::Start, false));
+  // whitespace in character classes and escaped whitespace is unchanged
+  assert_eq!(build_flex_space_pattern(r#"a[ ]b\ c"#), r#"a[ ]b\ c"#);
+  assert_eq!(build_flex_space_pattern("a[] ]  b"), "a[] ][\\s\u{85}\u{a0}\u{1680}\u{2000}-\u{200a}\u{2028}\u{2029}\u{202f}\u{205f}\u{3000}]+b");
+  // a quantifier applies to the whole run
+  assert!("dataController".pattern_match_flex_space("data ?controller", true));
+  assert!("controller only".match_word_flex_space("controller   only", false));
+  let replaced = sample_text.to_string().replace_word_flex_space("data controller", "processor", true);
+  assert_eq!(replaced, "Terms of\nservice apply to the  processor\tonly.");
+  assert_eq!("terms\u{2028}of \r\n use".to_string().replace_word_flex_space("terms of use", "ToU", false), "ToU");
+  // the case-insensitive flag also applies when the pattern starts with an optional space group
+  assert!("xfoo".pattern_match_flex_space(" ?FOO", true));
+  // patterns in whitespace-insensitive mode are unchanged
+  assert_eq!(build_flex_space_pattern("(?x) data \\ controller"), "(?x) data \\ controller");
+  assert_eq!(build_flex_space_pattern("(?ix:a b)"), "(?ix:a b)");
+  assert_ne!(build_flex_space_pattern("(?i-x)a b"), "(?i-x)a b");
+  assert!("data controller".pattern_match_flex_space("(?x) data \\ controller", false));
+}
+
+#[test]
+fn test_inflected_words() {
+  let variants: Vec<String> = inflect_word("mouse").into_iter().map(|(_, variant)| variant).collect();
+  assert_eq!(variants, vec!["mouse", "mice", "mousing", "moused"]);
+  let variants = inflect_word("stop");
+  assert_eq!(variants, vec![(Inflection::Base, "stop".to_string()), (Inflection::Plural, "stops".to_string()), (Inflection::PresentParticiple, "stopping".to_string()), (Inflection::Past, "stopped".to_string())]);
+  assert_eq!(inflect_word("write").into_iter().map(|(_, variant)| variant).collect::<Vec<String>>(), vec!["write", "writes", "writing", "wrote", "written"]);
+  assert_eq!(inflect_word("City").into_iter().map(|(_, variant)| variant).collect::<Vec<String>>(), vec!["City", "Cities", "Citying", "Citied"]);
+  let sample_text = "Two mice ran past the runway while the cat was running.";
+  assert!(!sample_text.match_word_ci("mouse"));
+  assert!(sample_text.match_word_inflected_ci("mouse"));
+  assert!(sample_text.match_word_inflected("run", false));
+  assert!(!"Watch the runway".match_word_inflected_ci("run"));
+  assert!("Boxes and wishes".match_word_inflected("box", true));
+  assert!(!"Boxes and wishes".match_word_inflected("box", false));
+  // each inflection is replaced by the same inflection of the replacement
+  let replaced = sample_text.to_string().replace_word_inflected("mouse", "rat", true);
+  assert_eq!(replaced, "Two rats ran past the runway while the cat was running.");
+  let replaced = sample_text.to_string().replace_word_inflected("run", "walk", true);
+  assert_eq!(replaced, "Two mice walked past the runway while the cat was walking.");
+  // lower-case replacements take the capitalisation of the matched word
+  let replaced = "Children, CHILD and child".to_string().replace_word_inflected("child", "kid", true);
+  assert_eq!(replaced, "Kids, KID and kid");
+  let replaced = "She stopped, then tried again".to_string().replace_words_inflected(&[("stop", "pause"), ("try", "go")], false);
+  assert_eq!(replaced, "She paused, then went again");
+  // final consonants are doubled after one vowel group or a stressed last syllable
+  let variants: Vec<String> = inflect_word("begin").into_iter().map(|(_, variant)| variant).collect();
+  assert_eq!(variants, vec!["begin", "begins", "beginning", "began", "begun"]);
+  assert!("She kept forgetting".match_word_inflected_ci("forget"));
+  assert!("Visiting".match_word_inflected_ci("visit"));
+  // u after q is not a vowel, so quiz doubles its z
+  let variants: Vec<String> = inflect_word("quiz").into_iter().map(|(_, variant)| variant).collect();
+  assert_eq!(variants, vec!["quiz", "quizzes", "quizzing", "quizzed"]);
+  assert!("They kept quitting".match_word_inflected("quit", false));
+  // words ending in non-ASCII letters are inflected by characters. \b only treats ASCII as word characters with lite
+  #[cfg(not(all(feature = "lite", not(feature = "fancy"))))]
+  assert!("Der Fuß".match_word_inflected_ci("Fuß"));
+  let variants: Vec<String> = inflect_word("Koč").into_iter().map(|(_, variant)| variant).collect();
+  assert_eq!(variants, vec!["Koč", "Kočs", "Koččing", "Koččed"]);
+}